@@ -3,6 +3,7 @@ import { useCallback, useState } from 'react';
 import { useWallet } from '@solana/wallet-adapter-react';
 import { useConnection } from '@solana/wallet-adapter-react';
 import { getProgram } from '../lib/anchorClient';
+import { deriveLeaderboardPda } from '../lib/pdas';
 import { BN } from '@coral-xyz/anchor';
 import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
 import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from '@solana/spl-token';
@@ -37,9 +38,10 @@ export function ContributeCard({ campaignAddress, payMint }: ContributeCardProps
         campaignAddress.toBuffer(),
         wallet.publicKey.toBuffer(),
       ], program.programId);
+      const [leaderboardPda] = deriveLeaderboardPda(campaignAddress, program.programId);
 
       await program.methods
-        .contribute(new BN(amount))
+        .contribute(new BN(amount), null, null, null, null)
         .accounts({
           contributor: wallet.publicKey,
           campaign: campaignAddress,
@@ -47,6 +49,10 @@ export function ContributeCard({ campaignAddress, payMint }: ContributeCardProps
           contributorAta,
           vault,
           contribution: contributionPda,
+          leaderboard: leaderboardPda,
+          receiptMint: null,
+          receiptAccount: null,
+          referral: null,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -79,6 +85,7 @@ export function ContributeCard({ campaignAddress, payMint }: ContributeCardProps
         campaignAddress.toBuffer(),
         wallet.publicKey.toBuffer(),
       ], program.programId);
+      const [leaderboardPda] = deriveLeaderboardPda(campaignAddress, program.programId);
 
       await program.methods
         .refund()
@@ -89,6 +96,7 @@ export function ContributeCard({ campaignAddress, payMint }: ContributeCardProps
           contributorAta,
           vault,
           contribution: contributionPda,
+          leaderboard: leaderboardPda,
           tokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
@@ -2,9 +2,9 @@
 import { useCallback, useState } from 'react';
 import { useWallet } from '@solana/wallet-adapter-react';
 import { useConnection } from '@solana/wallet-adapter-react';
-import { getProgram } from '../lib/anchorClient';
+import { defaultCampaignOptions, getProgram } from '../lib/anchorClient';
 import { PublicKey } from '@solana/web3.js';
-import { deriveCampaignPda } from '../lib/pdas';
+import { deriveCampaignPda, deriveLeaderboardPda } from '../lib/pdas';
 import { BN } from '@coral-xyz/anchor';
 import { getAssociatedTokenAddressSync } from '@solana/spl-token';
 import { SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
@@ -31,16 +31,18 @@ export function CreateCampaignForm() {
       const payMint = new PublicKey(process.env.NEXT_PUBLIC_USDC_MINT!);
       const [campaignPda] = deriveCampaignPda(wallet.publicKey, program.programId);
       const vaultAta = getAssociatedTokenAddressSync(payMint, campaignPda, true);
+      const [leaderboardPda] = deriveLeaderboardPda(campaignPda, program.programId);
       const nowSec = Math.floor(Date.now() / 1000);
       const deadline = nowSec + parseInt(deadlineMin, 10) * 60;
 
       await program.methods
-        .initCampaign(new BN(target), new BN(deadline))
+        .initCampaign(new BN(target), new BN(deadline), defaultCampaignOptions())
         .accounts({
           creator: wallet.publicKey,
           payMint,
           campaign: campaignPda,
           vault: vaultAta,
+          leaderboard: leaderboardPda,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
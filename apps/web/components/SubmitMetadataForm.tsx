@@ -37,7 +37,7 @@ export function SubmitMetadataForm() {
       const [campaignPda] = deriveCampaignPda(wallet.publicKey, program.programId);
 
       await program.methods
-        .submitMetadata(uri, Array.from(hexToBytes(hashHex)))
+        .submitMetadata(uri, Array.from(hexToBytes(hashHex)), { sha256: {} })
         .accounts({ submitter: wallet.publicKey, campaign: campaignPda })
         .rpc();
       setMessage('Metadata submitted');
@@ -94,7 +94,7 @@ export function UserFriendlyMetadataForm({ onClose }: { onClose?: () => void })
       );
 
       await program.methods
-        .submitMetadata(uri, hashBytes)
+        .submitMetadata(uri, hashBytes, { sha256: {} })
         .accounts({ submitter: wallet.publicKey, campaign: campaignPda })
         .rpc();
         
@@ -1,6 +1,7 @@
 import { Connection, PublicKey, Transaction, TransactionSignature } from '@solana/web3.js';
 import { AnchorProvider, Program } from '@coral-xyz/anchor';
 import idl from '../idl/cto_dex_escrow.json';
+import { CampaignOptions } from '../types/cto_dex_escrow';
 
 // Simple program interface
 export interface SimpleProgram extends Program {
@@ -77,6 +78,26 @@ export async function fetchCampaign(
   }
 }
 
+// init_campaign options with every optional feature turned off
+export function defaultCampaignOptions(): CampaignOptions {
+  return {
+    minContributors: null,
+    contributionCap: null,
+    allowlistRoot: null,
+    withdrawalPenaltyBps: null,
+    withdrawalCutoffSecs: null,
+    fundingMode: null,
+    softCap: null,
+    hardCap: null,
+    partialFill: false,
+    autoFinalize: false,
+    startUnix: null,
+    extensionRequiresConsent: false,
+    tiers: [],
+    referralRewardBps: null,
+  };
+}
+
 // Basic campaign creator
 export async function createCampaign(
   program: Program,
@@ -87,13 +108,14 @@ export async function createCampaign(
       .initCampaign(
         campaignData.targetAmount,
         campaignData.deadline,
-        campaignData.payMint
+        campaignData.options ?? defaultCampaignOptions()
       )
       .accounts({
         campaign: campaignData.campaignPubkey,
         creator: campaignData.creator,
         payMint: campaignData.payMint,
         vault: campaignData.vault,
+        leaderboard: campaignData.leaderboard,
         tokenProgram: campaignData.tokenProgram,
         systemProgram: campaignData.systemProgram,
         rent: campaignData.rent
@@ -114,12 +136,24 @@ export async function contribute(
 ): Promise<TransactionSignature> {
   try {
     const tx = await program.methods
-      .contribute(contributionData.amount)
+      .contribute(
+        contributionData.amount,
+        contributionData.allowlist ?? null,
+        contributionData.referrer ?? null,
+        contributionData.memo ?? null,
+        contributionData.beneficiary ?? null
+      )
       .accounts({
         campaign: contributionData.campaign,
         contributor: contributionData.contributor,
         contributorAta: contributionData.contributorAta,
         vault: contributionData.vault,
+        // Seeded by the beneficiary when one is named
+        contribution: contributionData.contribution,
+        leaderboard: contributionData.leaderboard,
+        receiptMint: contributionData.receiptMint ?? null,
+        receiptAccount: contributionData.receiptAccount ?? null,
+        referral: contributionData.referral ?? null,
         tokenProgram: contributionData.tokenProgram,
         systemProgram: contributionData.systemProgram
       })
@@ -15,6 +15,17 @@ export function deriveContributionPda(campaign: PublicKey, contributor: PublicKe
   ], programId);
 }
 
+export function deriveLeaderboardPda(campaign: PublicKey, programId: PublicKey) {
+  return PublicKey.findProgramAddressSync([
+    Buffer.from('leaderboard'),
+    campaign.toBuffer(),
+  ], programId);
+}
+
+// sha256("account:Campaign")[..8], base58-encoded; unlike the account size it
+// doesn't change when Campaign gains fields
+const CAMPAIGN_DISCRIMINATOR = '9PayLifPYdD';
+
 // Helper function to get all campaigns for a specific token mint
 // Note: This is a client-side utility since the program doesn't index by token
 export async function findCampaignsByToken(
@@ -27,7 +38,10 @@ export async function findCampaignsByToken(
     const accounts = await connection.getProgramAccounts(programId, {
       filters: [
         {
-          dataSize: 8 + 32 + 32 + 1 + 8 + 8 + 8 + 1 + 32 + 8 + 4 + 256 + 32 + 32 + 1, // Campaign account size
+          memcmp: {
+            offset: 0,
+            bytes: CAMPAIGN_DISCRIMINATOR,
+          },
         },
         {
           memcmp: {
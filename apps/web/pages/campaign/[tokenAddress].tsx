@@ -4,7 +4,7 @@ import { useWallet } from '@solana/wallet-adapter-react';
 import { useConnection } from '@solana/wallet-adapter-react';
 import { PublicKey } from '@solana/web3.js';
 import { getProgram } from '../../lib/anchorClient';
-import { findCampaignsByToken } from '../../lib/pdas';
+import { deriveLeaderboardPda, findCampaignsByToken } from '../../lib/pdas';
 import { BN } from '@coral-xyz/anchor';
 import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from '@solana/spl-token';
 import { SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
@@ -120,11 +120,12 @@ export default function CampaignPage() {
         campaignAddress.toBuffer(),
         wallet.publicKey.toBuffer(),
       ], program.programId);
+      const [leaderboardPda] = deriveLeaderboardPda(campaignAddress, program.programId);
       
       const amount = parseFloat(contributionAmount) * 1_000_000; // Convert to minor units
 
       await program.methods
-        .contribute(new BN(amount))
+        .contribute(new BN(amount), null, null, null, null)
         .accounts({
           contributor: wallet.publicKey,
           campaign: campaignAddress,
@@ -132,6 +133,10 @@ export default function CampaignPage() {
           contributorAta,
           vault,
           contribution: contributionPda,
+          leaderboard: leaderboardPda,
+          receiptMint: null,
+          receiptAccount: null,
+          referral: null,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
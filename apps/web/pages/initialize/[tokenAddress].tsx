@@ -4,8 +4,8 @@ import { useWallet } from '@solana/wallet-adapter-react';
 import { useConnection } from '@solana/wallet-adapter-react';
 import WalletButton from '../../components/WalletButton';
 import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
-import { getProgram } from '../../lib/anchorClient';
-import { deriveCampaignPda } from '../../lib/pdas';
+import { defaultCampaignOptions, getProgram } from '../../lib/anchorClient';
+import { deriveCampaignPda, deriveLeaderboardPda } from '../../lib/pdas';
 import { BN } from '@coral-xyz/anchor';
 import { getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID } from '@solana/spl-token';
 
@@ -54,6 +54,7 @@ export default function InitializeCampaignPage() {
       const payMint = new PublicKey(process.env.NEXT_PUBLIC_USDC_MINT);
       const [campaignPda] = deriveCampaignPda(wallet.publicKey, program.programId);
       const vaultAta = getAssociatedTokenAddressSync(payMint, campaignPda, true);
+      const [leaderboardPda] = deriveLeaderboardPda(campaignPda, program.programId);
       
       const nowSec = Math.floor(Date.now() / 1000);
       const deadline = nowSec + (durationHoursNum * 60 * 60);
@@ -85,12 +86,13 @@ export default function InitializeCampaignPage() {
       setMessage('[INFO] Submitting transaction to blockchain...');
       
       const txSignature = await program.methods
-        .initCampaign(targetBN, deadlineBN)
+        .initCampaign(targetBN, deadlineBN, defaultCampaignOptions())
         .accounts({
           creator: wallet.publicKey,
           payMint,
           campaign: campaignPda,
           vault: vaultAta,
+          leaderboard: leaderboardPda,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -7,14 +7,47 @@ export interface Campaign {
   bump: number;
   targetAmount: BN;
   totalContributed: BN;
+  startTime: BN;
   deadline: BN;
   status: number;
   topContributor: PublicKey;
   topContributorAmount: BN;
+  metadataAuthority: PublicKey;
   metadataUri: string;
   metadataHash: number[];
+  metadataHashAlgorithm: number;
+  metadataVersion: number;
+  metadataSubmittedAt: BN;
+  metadataApproveWeight: BN;
+  metadataRejectWeight: BN;
   merchantHash: number[];
   merchantHashSet: boolean;
+  delegateAuthority: PublicKey;
+  contributorCount: number;
+  minContributors: number;
+  maxPerContributor: BN;
+  allowlistRoot: number[];
+  withdrawalPenaltyBps: number;
+  withdrawalCutoff: BN;
+  fundingMode: number;
+  softCap: BN;
+  hardCap: BN;
+  partialFill: boolean;
+  autoFinalize: boolean;
+  extensionRequiresConsent: boolean;
+  deadlineExtended: boolean;
+  extendedAt: BN;
+  proposedDeadline: BN;
+  extensionApproveWeight: BN;
+  extensionRejectWeight: BN;
+  mergeTarget: PublicKey;
+  receiptMint: PublicKey;
+  tiers: Tier[];
+  referralRewardBps: number;
+  referredTotal: BN;
+  referralReserve: BN;
+  reentrancyGuard: boolean;
+  lastOperationTimestamp: BN;
 }
 
 export interface Contribution {
@@ -22,6 +55,99 @@ export interface Contribution {
   campaign: PublicKey;
   amount: BN;
   refunded: boolean;
+  metadataVoteVersion: number;
+  metadataVoteApprove: boolean;
+  metadataVoteWeight: BN;
+  extensionVote: number;
+  extensionVoteWeight: BN;
+  refundRecipient: PublicKey;
+  badgeMinted: boolean;
+  badgeTier: number;
+  badgeRank: number;
+  tier: number;
+  referrer: PublicKey;
+  memo: string;
+  lastDepositAt: BN;
+}
+
+export interface Referral {
+  campaign: PublicKey;
+  referrer: PublicKey;
+  bump: number;
+  referredAmount: BN;
+  referredCount: number;
+  rewardClaimed: boolean;
+}
+
+export interface Leaderboard {
+  campaign: PublicKey;
+  bump: number;
+  len: number;
+  nextSeq: BN;
+  entries: LeaderboardEntry[];
+}
+
+export interface CampaignOptions {
+  minContributors: number | null;
+  contributionCap: ContributionCap | null;
+  allowlistRoot: number[] | null;
+  withdrawalPenaltyBps: number | null;
+  withdrawalCutoffSecs: BN | null;
+  fundingMode: FundingMode | null;
+  softCap: BN | null;
+  hardCap: BN | null;
+  partialFill: boolean;
+  autoFinalize: boolean;
+  startUnix: BN | null;
+  extensionRequiresConsent: boolean;
+  tiers: TierConfig[];
+  referralRewardBps: number | null;
+}
+
+export interface TierConfig {
+  minAmount: BN;
+  label: string;
+  maxSlots: number;
+}
+
+export interface Tier {
+  minAmount: BN;
+  label: string;
+  maxSlots: number;
+  filled: number;
+}
+
+export type ContributionCap =
+  | { absolute: { 0: BN } }
+  | { percentOfTargetBps: { 0: number } };
+
+export interface AllowlistProof {
+  cap: BN;
+  proof: number[][];
+}
+
+export interface LeaderboardEntry {
+  contributor: PublicKey;
+  amount: BN;
+  seq: BN;
+}
+
+export type FundingMode =
+  | { allOrNothing: {} }
+  | { flexible: {} };
+
+export type MetadataHashAlgorithm =
+  | { sha256: {} }
+  | { keccak256: {} }
+  | { blake3: {} }
+  | { ipfsMultihash: {} };
+
+export interface ContributionMemo {
+  campaign: PublicKey;
+  contributor: PublicKey;
+  payer: PublicKey;
+  amount: BN;
+  memo: string;
 }
 
 export type CtoDexEscrowProgram = {
@@ -35,6 +161,7 @@ export type CtoDexEscrowProgram = {
         { name: 'payMint'; isMut: false; isSigner: false },
         { name: 'campaign'; isMut: true; isSigner: false },
         { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
         { name: 'systemProgram'; isMut: false; isSigner: false },
         { name: 'tokenProgram'; isMut: false; isSigner: false },
         { name: 'associatedTokenProgram'; isMut: false; isSigner: false },
@@ -42,8 +169,31 @@ export type CtoDexEscrowProgram = {
       ];
       args: [
         { name: 'targetAmount'; type: 'u64' },
-        { name: 'deadlineUnix'; type: 'i64' }
+        { name: 'deadlineUnix'; type: 'i64' },
+        { name: 'options'; type: { defined: 'CampaignOptions' } }
+      ];
+    },
+    {
+      name: 'enableReceipts';
+      accounts: [
+        { name: 'creator'; isMut: true; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'receiptMint'; isMut: true; isSigner: false },
+        { name: 'systemProgram'; isMut: false; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
       ];
+      args: [];
+    },
+    {
+      name: 'registerReferrer';
+      accounts: [
+        { name: 'referrer'; isMut: true; isSigner: true },
+        { name: 'campaign'; isMut: false; isSigner: false },
+        { name: 'referral'; isMut: true; isSigner: false },
+        { name: 'systemProgram'; isMut: false; isSigner: false }
+      ];
+      args: [];
     },
     {
       name: 'contribute';
@@ -54,12 +204,22 @@ export type CtoDexEscrowProgram = {
         { name: 'contributorAta'; isMut: true; isSigner: false },
         { name: 'vault'; isMut: true; isSigner: false },
         { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'receiptMint'; isMut: true; isSigner: false; isOptional: true },
+        { name: 'receiptAccount'; isMut: true; isSigner: false; isOptional: true },
+        { name: 'referral'; isMut: true; isSigner: false; isOptional: true },
         { name: 'systemProgram'; isMut: false; isSigner: false },
         { name: 'tokenProgram'; isMut: false; isSigner: false },
         { name: 'associatedTokenProgram'; isMut: false; isSigner: false },
         { name: 'rent'; isMut: false; isSigner: false }
       ];
-      args: [{ name: 'amount'; type: 'u64' }];
+      args: [
+        { name: 'amount'; type: 'u64' },
+        { name: 'allowlist'; type: { option: { defined: 'AllowlistProof' } } },
+        { name: 'referrer'; type: { option: 'publicKey' } },
+        { name: 'memo'; type: { option: 'string' } },
+        { name: 'beneficiary'; type: { option: 'publicKey' } }
+      ];
     },
     {
       name: 'submitMetadata';
@@ -69,12 +229,26 @@ export type CtoDexEscrowProgram = {
       ];
       args: [
         { name: 'uri'; type: 'string' },
-        { name: 'metadataHash'; type: { array: ['u8', 32] } }
+        { name: 'metadataHash'; type: { array: ['u8', 32] } },
+        { name: 'hashAlgorithm'; type: { defined: 'MetadataHashAlgorithm' } }
+      ];
+    },
+    {
+      name: 'voteMetadata';
+      accounts: [
+        { name: 'contributor'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'contribution'; isMut: true; isSigner: false }
+      ];
+      args: [
+        { name: 'approve'; type: 'bool' }
       ];
     },
     {
       name: 'finalize';
-      accounts: [{ name: 'campaign'; isMut: true; isSigner: false }];
+      accounts: [
+        { name: 'campaign'; isMut: true; isSigner: false }
+      ];
       args: [];
     },
     {
@@ -86,17 +260,200 @@ export type CtoDexEscrowProgram = {
         { name: 'contributorAta'; isMut: true; isSigner: false },
         { name: 'vault'; isMut: true; isSigner: false },
         { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [];
+    },
+    {
+      name: 'refundFor';
+      accounts: [
+        { name: 'cranker'; isMut: true; isSigner: true },
+        { name: 'contributor'; isMut: false; isSigner: false },
+        { name: 'recipient'; isMut: false; isSigner: false },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'recipientAta'; isMut: true; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'systemProgram'; isMut: false; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false },
+        { name: 'associatedTokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [];
+    },
+    {
+      name: 'batchRefund';
+      accounts: [
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [];
+    },
+    {
+      name: 'redeemReceipts';
+      accounts: [
+        { name: 'holder'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'receiptMint'; isMut: true; isSigner: false },
+        { name: 'holderReceiptAccount'; isMut: true; isSigner: false },
+        { name: 'destination'; isMut: true; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [
+        { name: 'amount'; type: 'u64' }
+      ];
+    },
+    {
+      name: 'withdrawContribution';
+      accounts: [
+        { name: 'contributor'; isMut: true; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'contributorAta'; isMut: true; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'creatorAta'; isMut: true; isSigner: false; isOptional: true },
+        { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'referral'; isMut: true; isSigner: false; isOptional: true },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [
+        { name: 'amount'; type: 'u64' }
+      ];
+    },
+    {
+      name: 'extendDeadline';
+      accounts: [
+        { name: 'creator'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false }
+      ];
+      args: [
+        { name: 'newDeadline'; type: 'i64' }
+      ];
+    },
+    {
+      name: 'voteDeadlineExtension';
+      accounts: [
+        { name: 'contributor'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'contribution'; isMut: true; isSigner: false }
+      ];
+      args: [
+        { name: 'approve'; type: 'bool' }
+      ];
+    },
+    {
+      name: 'migrateContribution';
+      accounts: [
+        { name: 'contributor'; isMut: true; isSigner: true },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'sourceCampaign'; isMut: true; isSigner: false },
+        { name: 'sourceVault'; isMut: true; isSigner: false },
+        { name: 'sourceContribution'; isMut: true; isSigner: false },
+        { name: 'sourceLeaderboard'; isMut: true; isSigner: false },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'systemProgram'; isMut: false; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [
+        { name: 'allowlist'; type: { option: { defined: 'AllowlistProof' } } }
+      ];
+    },
+    {
+      name: 'proposeMerge';
+      accounts: [
+        { name: 'creator'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false }
+      ];
+      args: [
+        { name: 'destination'; type: 'publicKey' }
+      ];
+    },
+    {
+      name: 'acceptMerge';
+      accounts: [
+        { name: 'creator'; isMut: false; isSigner: true },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'sourceCampaign'; isMut: true; isSigner: false },
+        { name: 'sourceVault'; isMut: true; isSigner: false },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [];
+    },
+    {
+      name: 'claimMergedContribution';
+      accounts: [
+        { name: 'contributor'; isMut: true; isSigner: true },
+        { name: 'sourceCampaign'; isMut: false; isSigner: false },
+        { name: 'sourceContribution'; isMut: true; isSigner: false },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'refundAta'; isMut: true; isSigner: false; isOptional: true },
+        { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'systemProgram'; isMut: false; isSigner: false },
         { name: 'tokenProgram'; isMut: false; isSigner: false }
       ];
       args: [];
     },
+    {
+      name: 'transferContribution';
+      accounts: [
+        { name: 'contributor'; isMut: true; isSigner: true },
+        { name: 'newOwner'; isMut: false; isSigner: false },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'newContribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: true; isSigner: false },
+        { name: 'systemProgram'; isMut: false; isSigner: false }
+      ];
+      args: [
+        { name: 'allowlist'; type: { option: { defined: 'AllowlistProof' } } }
+      ];
+    },
+    {
+      name: 'setRefundRecipient';
+      accounts: [
+        { name: 'contributor'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: false; isSigner: false },
+        { name: 'contribution'; isMut: true; isSigner: false }
+      ];
+      args: [
+        { name: 'recipient'; type: 'publicKey' }
+      ];
+    },
     {
       name: 'setMerchantHash';
       accounts: [
         { name: 'authority'; isMut: false; isSigner: true },
         { name: 'campaign'; isMut: true; isSigner: false }
       ];
-      args: [{ name: 'merchantHash'; type: { array: ['u8', 32] } }];
+      args: [
+        { name: 'merchantHash'; type: { array: ['u8', 32] } }
+      ];
+    },
+    {
+      name: 'setDelegateAuthority';
+      accounts: [
+        { name: 'creator'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false }
+      ];
+      args: [
+        { name: 'delegate'; type: 'publicKey' }
+      ];
     },
     {
       name: 'payout';
@@ -107,7 +464,45 @@ export type CtoDexEscrowProgram = {
         { name: 'merchantAta'; isMut: true; isSigner: false },
         { name: 'tokenProgram'; isMut: false; isSigner: false }
       ];
-      args: [{ name: 'amount'; type: 'u64' }];
+      args: [
+        { name: 'amount'; type: 'u64' }
+      ];
+    },
+    {
+      name: 'claimReferralReward';
+      accounts: [
+        { name: 'referrer'; isMut: false; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'payMint'; isMut: false; isSigner: false },
+        { name: 'vault'; isMut: true; isSigner: false },
+        { name: 'referral'; isMut: true; isSigner: false },
+        { name: 'referrerAta'; isMut: true; isSigner: false },
+        { name: 'tokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [];
+    },
+    {
+      name: 'mintBadge';
+      accounts: [
+        { name: 'contributor'; isMut: true; isSigner: true },
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'contribution'; isMut: true; isSigner: false },
+        { name: 'leaderboard'; isMut: false; isSigner: false },
+        { name: 'badgeMint'; isMut: true; isSigner: false },
+        { name: 'badgeAccount'; isMut: true; isSigner: false },
+        { name: 'systemProgram'; isMut: false; isSigner: false },
+        { name: 'token2022Program'; isMut: false; isSigner: false },
+        { name: 'associatedTokenProgram'; isMut: false; isSigner: false }
+      ];
+      args: [];
+    },
+    {
+      name: 'clearReentrancyGuard';
+      accounts: [
+        { name: 'campaign'; isMut: true; isSigner: false },
+        { name: 'authority'; isMut: false; isSigner: true }
+      ];
+      args: [];
     }
   ];
   accounts: [
@@ -121,14 +516,47 @@ export type CtoDexEscrowProgram = {
           { name: 'bump'; type: 'u8' },
           { name: 'targetAmount'; type: 'u64' },
           { name: 'totalContributed'; type: 'u64' },
+          { name: 'startTime'; type: 'i64' },
           { name: 'deadline'; type: 'i64' },
           { name: 'status'; type: 'u8' },
           { name: 'topContributor'; type: 'publicKey' },
           { name: 'topContributorAmount'; type: 'u64' },
+          { name: 'metadataAuthority'; type: 'publicKey' },
           { name: 'metadataUri'; type: 'string' },
           { name: 'metadataHash'; type: { array: ['u8', 32] } },
+          { name: 'metadataHashAlgorithm'; type: 'u8' },
+          { name: 'metadataVersion'; type: 'u32' },
+          { name: 'metadataSubmittedAt'; type: 'i64' },
+          { name: 'metadataApproveWeight'; type: 'u64' },
+          { name: 'metadataRejectWeight'; type: 'u64' },
           { name: 'merchantHash'; type: { array: ['u8', 32] } },
-          { name: 'merchantHashSet'; type: 'bool' }
+          { name: 'merchantHashSet'; type: 'bool' },
+          { name: 'delegateAuthority'; type: 'publicKey' },
+          { name: 'contributorCount'; type: 'u32' },
+          { name: 'minContributors'; type: 'u32' },
+          { name: 'maxPerContributor'; type: 'u64' },
+          { name: 'allowlistRoot'; type: { array: ['u8', 32] } },
+          { name: 'withdrawalPenaltyBps'; type: 'u16' },
+          { name: 'withdrawalCutoff'; type: 'i64' },
+          { name: 'fundingMode'; type: 'u8' },
+          { name: 'softCap'; type: 'u64' },
+          { name: 'hardCap'; type: 'u64' },
+          { name: 'partialFill'; type: 'bool' },
+          { name: 'autoFinalize'; type: 'bool' },
+          { name: 'extensionRequiresConsent'; type: 'bool' },
+          { name: 'deadlineExtended'; type: 'bool' },
+          { name: 'extendedAt'; type: 'i64' },
+          { name: 'proposedDeadline'; type: 'i64' },
+          { name: 'extensionApproveWeight'; type: 'u64' },
+          { name: 'extensionRejectWeight'; type: 'u64' },
+          { name: 'mergeTarget'; type: 'publicKey' },
+          { name: 'receiptMint'; type: 'publicKey' },
+          { name: 'tiers'; type: { vec: { defined: 'Tier' } } },
+          { name: 'referralRewardBps'; type: 'u16' },
+          { name: 'referredTotal'; type: 'u64' },
+          { name: 'referralReserve'; type: 'u64' },
+          { name: 'reentrancyGuard'; type: 'bool' },
+          { name: 'lastOperationTimestamp'; type: 'i64' }
         ];
       };
     },
@@ -140,12 +568,163 @@ export type CtoDexEscrowProgram = {
           { name: 'contributor'; type: 'publicKey' },
           { name: 'campaign'; type: 'publicKey' },
           { name: 'amount'; type: 'u64' },
-          { name: 'refunded'; type: 'bool' }
+          { name: 'refunded'; type: 'bool' },
+          { name: 'metadataVoteVersion'; type: 'u32' },
+          { name: 'metadataVoteApprove'; type: 'bool' },
+          { name: 'metadataVoteWeight'; type: 'u64' },
+          { name: 'extensionVote'; type: 'u8' },
+          { name: 'extensionVoteWeight'; type: 'u64' },
+          { name: 'refundRecipient'; type: 'publicKey' },
+          { name: 'badgeMinted'; type: 'bool' },
+          { name: 'badgeTier'; type: 'u8' },
+          { name: 'badgeRank'; type: 'u8' },
+          { name: 'tier'; type: 'u8' },
+          { name: 'referrer'; type: 'publicKey' },
+          { name: 'memo'; type: 'string' },
+          { name: 'lastDepositAt'; type: 'i64' }
         ];
       };
+    },
+    {
+      name: 'referral';
+      type: {
+        kind: 'struct';
+        fields: [
+          { name: 'campaign'; type: 'publicKey' },
+          { name: 'referrer'; type: 'publicKey' },
+          { name: 'bump'; type: 'u8' },
+          { name: 'referredAmount'; type: 'u64' },
+          { name: 'referredCount'; type: 'u32' },
+          { name: 'rewardClaimed'; type: 'bool' }
+        ];
+      };
+    },
+    {
+      name: 'leaderboard';
+      type: {
+        kind: 'struct';
+        fields: [
+          { name: 'campaign'; type: 'publicKey' },
+          { name: 'bump'; type: 'u8' },
+          { name: 'len'; type: 'u8' },
+          { name: 'nextSeq'; type: 'u64' },
+          { name: 'entries'; type: { array: [{ defined: 'LeaderboardEntry' }, 10] } }
+        ];
+      };
+    }
+  ];
+  types: [
+    {
+      name: 'CampaignOptions';
+      type: {
+        kind: 'struct';
+        fields: [
+          { name: 'minContributors'; type: { option: 'u32' } },
+          { name: 'contributionCap'; type: { option: { defined: 'ContributionCap' } } },
+          { name: 'allowlistRoot'; type: { option: { array: ['u8', 32] } } },
+          { name: 'withdrawalPenaltyBps'; type: { option: 'u16' } },
+          { name: 'withdrawalCutoffSecs'; type: { option: 'i64' } },
+          { name: 'fundingMode'; type: { option: { defined: 'FundingMode' } } },
+          { name: 'softCap'; type: { option: 'u64' } },
+          { name: 'hardCap'; type: { option: 'u64' } },
+          { name: 'partialFill'; type: 'bool' },
+          { name: 'autoFinalize'; type: 'bool' },
+          { name: 'startUnix'; type: { option: 'i64' } },
+          { name: 'extensionRequiresConsent'; type: 'bool' },
+          { name: 'tiers'; type: { vec: { defined: 'TierConfig' } } },
+          { name: 'referralRewardBps'; type: { option: 'u16' } }
+        ];
+      };
+    },
+    {
+      name: 'TierConfig';
+      type: {
+        kind: 'struct';
+        fields: [
+          { name: 'minAmount'; type: 'u64' },
+          { name: 'label'; type: 'string' },
+          { name: 'maxSlots'; type: 'u32' }
+        ];
+      };
+    },
+    {
+      name: 'Tier';
+      type: {
+        kind: 'struct';
+        fields: [
+          { name: 'minAmount'; type: 'u64' },
+          { name: 'label'; type: 'string' },
+          { name: 'maxSlots'; type: 'u32' },
+          { name: 'filled'; type: 'u32' }
+        ];
+      };
+    },
+    {
+      name: 'ContributionCap';
+      type: {
+        kind: 'enum';
+        variants: [
+          { name: 'Absolute'; fields: ['u64'] },
+          { name: 'PercentOfTargetBps'; fields: ['u16'] }
+        ];
+      };
+    },
+    {
+      name: 'AllowlistProof';
+      type: {
+        kind: 'struct';
+        fields: [
+          { name: 'cap'; type: 'u64' },
+          { name: 'proof'; type: { vec: { array: ['u8', 32] } } }
+        ];
+      };
+    },
+    {
+      name: 'LeaderboardEntry';
+      type: {
+        kind: 'struct';
+        fields: [
+          { name: 'contributor'; type: 'publicKey' },
+          { name: 'amount'; type: 'u64' },
+          { name: 'seq'; type: 'u64' }
+        ];
+      };
+    },
+    {
+      name: 'FundingMode';
+      type: {
+        kind: 'enum';
+        variants: [
+          { name: 'AllOrNothing' },
+          { name: 'Flexible' }
+        ];
+      };
+    },
+    {
+      name: 'MetadataHashAlgorithm';
+      type: {
+        kind: 'enum';
+        variants: [
+          { name: 'Sha256' },
+          { name: 'Keccak256' },
+          { name: 'Blake3' },
+          { name: 'IpfsMultihash' }
+        ];
+      };
+    }
+  ];
+  events: [
+    {
+      name: 'ContributionMemo';
+      fields: [
+        { name: 'campaign'; type: 'publicKey'; index: false },
+        { name: 'contributor'; type: 'publicKey'; index: false },
+        { name: 'payer'; type: 'publicKey'; index: false },
+        { name: 'amount'; type: 'u64'; index: false },
+        { name: 'memo'; type: 'string'; index: false }
+      ];
     }
   ];
-  types: [];
   errors: [
     { code: 6000; name: 'InvalidAmount'; msg: 'Invalid amount' },
     { code: 6001; name: 'InvalidDeadline'; msg: 'Invalid deadline' },
@@ -158,6 +737,65 @@ export type CtoDexEscrowProgram = {
     { code: 6008; name: 'AlreadyRefunded'; msg: 'Already refunded' },
     { code: 6009; name: 'NothingToRefund'; msg: 'Nothing to refund' },
     { code: 6010; name: 'MerchantHashNotSet'; msg: 'Merchant hash not set' },
-    { code: 6011; name: 'MerchantHashMismatch'; msg: 'Merchant hash mismatch' }
+    { code: 6011; name: 'MerchantHashMismatch'; msg: 'Merchant hash mismatch' },
+    { code: 6012; name: 'AmountTooSmall'; msg: 'Amount too small' },
+    { code: 6013; name: 'AmountTooLarge'; msg: 'Amount too large' },
+    { code: 6014; name: 'DurationTooShort'; msg: 'Duration too short' },
+    { code: 6015; name: 'DurationTooLong'; msg: 'Duration too long' },
+    { code: 6016; name: 'InsufficientRent'; msg: 'Insufficient rent' },
+    { code: 6017; name: 'InsufficientBalance'; msg: 'Insufficient balance' },
+    { code: 6018; name: 'ExceedsTarget'; msg: 'Exceeds hard cap' },
+    { code: 6019; name: 'EmptyUri'; msg: 'Empty URI' },
+    { code: 6020; name: 'InvalidUriFormat'; msg: 'Invalid URI format' },
+    { code: 6021; name: 'InvalidMerchantHash'; msg: 'Invalid merchant hash' },
+    { code: 6022; name: 'InvalidMetadataHash'; msg: 'Invalid metadata hash' },
+    { code: 6023; name: 'ExceedsCampaignTotal'; msg: 'Exceeds campaign total' },
+    { code: 6024; name: 'InsufficientVaultBalance'; msg: 'Insufficient vault balance' },
+    { code: 6025; name: 'ReentrancyDetected'; msg: 'Reentrancy detected' },
+    { code: 6026; name: 'CannotClearGuard'; msg: 'Cannot clear guard' },
+    { code: 6027; name: 'InvalidHashAlgorithm'; msg: 'Invalid hash algorithm' },
+    { code: 6028; name: 'MetadataNotSubmitted'; msg: 'Metadata not submitted' },
+    { code: 6029; name: 'MetadataNotApproved'; msg: 'Metadata not approved' },
+    { code: 6030; name: 'VotingClosed'; msg: 'Voting closed' },
+    { code: 6031; name: 'AlreadyVoted'; msg: 'Already voted' },
+    { code: 6032; name: 'NothingToVote'; msg: 'Nothing to vote with' },
+    { code: 6033; name: 'MerchantHashAlreadySet'; msg: 'Merchant hash already set' },
+    { code: 6034; name: 'InvalidContributionCap'; msg: 'Invalid contribution cap' },
+    { code: 6035; name: 'ContributorCapExceeded'; msg: 'Contributor cap exceeded' },
+    { code: 6036; name: 'NotAllowlisted'; msg: 'Not allowlisted' },
+    { code: 6037; name: 'AllowlistProofTooLong'; msg: 'Allowlist proof too long' },
+    { code: 6038; name: 'AllowlistCapExceeded'; msg: 'Allowlist cap exceeded' },
+    { code: 6039; name: 'InvalidWithdrawalTerms'; msg: 'Invalid withdrawal terms' },
+    { code: 6040; name: 'WithdrawalCutoff'; msg: 'Withdrawals are closed' },
+    { code: 6041; name: 'MissingCreatorAccount'; msg: 'Missing creator account' },
+    { code: 6042; name: 'InvalidSoftCap'; msg: 'Invalid soft cap' },
+    { code: 6043; name: 'InvalidHardCap'; msg: 'Invalid hard cap' },
+    { code: 6044; name: 'InvalidStartTime'; msg: 'Invalid start time' },
+    { code: 6045; name: 'CampaignNotStarted'; msg: 'Campaign not started' },
+    { code: 6046; name: 'DeadlineAlreadyExtended'; msg: 'Deadline already extended' },
+    { code: 6047; name: 'NoExtensionProposed'; msg: 'No extension proposed' },
+    { code: 6048; name: 'PayMintMismatch'; msg: 'Pay mint mismatch' },
+    { code: 6049; name: 'InvalidMergeTarget'; msg: 'Invalid merge target' },
+    { code: 6050; name: 'InvalidBatch'; msg: 'Invalid batch' },
+    { code: 6051; name: 'InvalidContributionAccount'; msg: 'Invalid contribution account' },
+    { code: 6052; name: 'InvalidRefundAccount'; msg: 'Invalid refund account' },
+    { code: 6053; name: 'InvalidTransferTarget'; msg: 'Invalid transfer target' },
+    { code: 6054; name: 'ReceiptsLocked'; msg: 'Receipts must be enabled before the first contribution' },
+    { code: 6055; name: 'ReceiptAccountsRequired'; msg: 'Receipt mint and account required' },
+    { code: 6056; name: 'InvalidReceiptMint'; msg: 'Invalid receipt mint' },
+    { code: 6057; name: 'ReceiptCampaign'; msg: 'Not supported for receipt campaigns' },
+    { code: 6058; name: 'BadgeAlreadyMinted'; msg: 'Badge already minted' },
+    { code: 6059; name: 'InvalidTiers'; msg: 'Invalid tiers' },
+    { code: 6060; name: 'InvalidReferralReward'; msg: 'Invalid referral reward' },
+    { code: 6061; name: 'InvalidReferrer'; msg: 'Invalid referrer' },
+    { code: 6062; name: 'ReferralAccountRequired'; msg: 'Referral account required' },
+    { code: 6063; name: 'ReferralRewardsUnfunded'; msg: 'Payout would not leave enough for referral rewards' },
+    { code: 6064; name: 'NothingToClaim'; msg: 'Nothing to claim' },
+    { code: 6065; name: 'RewardAlreadyClaimed'; msg: 'Reward already claimed' },
+    { code: 6066; name: 'InvalidMemo'; msg: 'Invalid memo' },
+    { code: 6067; name: 'NotEligibleForBadge'; msg: 'Contribution is not eligible for a badge' },
+    { code: 6068; name: 'RefundRecipientSet'; msg: 'Contribution has a refund recipient; refund it instead' },
+    { code: 6069; name: 'RefundRecipientMismatch'; msg: 'Refund recipients of the merged positions differ' },
+    { code: 6070; name: 'InvalidMinContributors'; msg: 'Minimum contributor count cannot be reached' }
   ];
 };
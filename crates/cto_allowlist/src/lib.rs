@@ -0,0 +1,241 @@
+//! Builds the Merkle allowlist committed in `CampaignOptions::allowlist_root`
+//! and the per-wallet proofs passed to `contribute`.
+//!
+//! Input is a CSV with one `wallet,cap` row per wallet. The header row is
+//! optional, `#` starts a comment, and an empty or `0` cap means the wallet is
+//! only limited by the campaign's own caps.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use cto_dex_escrow::{allowlist_leaf, allowlist_node, AllowlistProof};
+
+#[derive(Debug)]
+pub enum AllowlistError {
+    Csv(csv::Error),
+    InvalidWallet { line: u64, value: String },
+    InvalidCap { line: u64, value: String },
+    DuplicateWallet(Pubkey),
+    Empty,
+}
+
+impl fmt::Display for AllowlistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Csv(err) => write!(f, "invalid CSV: {err}"),
+            Self::InvalidWallet { line, value } => write!(f, "line {line}: invalid wallet `{value}`"),
+            Self::InvalidCap { line, value } => write!(f, "line {line}: invalid cap `{value}`"),
+            Self::DuplicateWallet(wallet) => write!(f, "wallet {wallet} is listed more than once"),
+            Self::Empty => write!(f, "allowlist has no wallets"),
+        }
+    }
+}
+
+impl std::error::Error for AllowlistError {}
+
+impl From<csv::Error> for AllowlistError {
+    fn from(err: csv::Error) -> Self {
+        Self::Csv(err)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllowlistEntry {
+    pub wallet: Pubkey,
+    pub cap: u64,
+}
+
+pub fn read_csv<R: Read>(reader: R) -> Result<Vec<AllowlistEntry>, AllowlistError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .comment(Some(b'#'))
+        .from_reader(reader);
+
+    let mut entries = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record?;
+        let line = record.position().map_or(0, |p| p.line());
+        let wallet = record.get(0).unwrap_or_default();
+        if wallet.is_empty() || (i == 0 && wallet.eq_ignore_ascii_case("wallet")) {
+            continue;
+        }
+
+        let wallet = Pubkey::from_str(wallet).map_err(|_| AllowlistError::InvalidWallet {
+            line,
+            value: wallet.to_string(),
+        })?;
+        let cap = match record.get(1).unwrap_or_default() {
+            "" => 0,
+            cap => cap.parse().map_err(|_| AllowlistError::InvalidCap {
+                line,
+                value: cap.to_string(),
+            })?,
+        };
+        entries.push(AllowlistEntry { wallet, cap });
+    }
+    Ok(entries)
+}
+
+pub struct AllowlistTree {
+    entries: Vec<AllowlistEntry>,
+    // layers[0] holds the leaves, the last layer holds the root
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl AllowlistTree {
+    pub fn new(entries: Vec<AllowlistEntry>) -> Result<Self, AllowlistError> {
+        if entries.is_empty() {
+            return Err(AllowlistError::Empty);
+        }
+        let mut seen = HashSet::with_capacity(entries.len());
+        if let Some(dup) = entries.iter().find(|e| !seen.insert(e.wallet)) {
+            return Err(AllowlistError::DuplicateWallet(dup.wallet));
+        }
+
+        let leaves: Vec<[u8; 32]> = entries.iter().map(|e| allowlist_leaf(&e.wallet, e.cap)).collect();
+        let mut layers = vec![leaves];
+        while let Some(layer) = layers.last().filter(|layer| layer.len() > 1) {
+            // An odd node out is carried up unchanged
+            let next = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => allowlist_node(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Ok(Self { entries, layers })
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.layers[self.layers.len() - 1][0]
+    }
+
+    pub fn entries(&self) -> &[AllowlistEntry] {
+        &self.entries
+    }
+
+    pub fn proof(&self, index: usize) -> Option<AllowlistProof> {
+        let entry = self.entries.get(index)?;
+        let mut proof = Vec::with_capacity(self.layers.len());
+        let mut index = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(AllowlistProof { cap: entry.cap, proof })
+    }
+
+    pub fn proof_for(&self, wallet: &Pubkey) -> Option<AllowlistProof> {
+        let index = self.entries.iter().position(|e| e.wallet == *wallet)?;
+        self.proof(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cto_dex_escrow::verify_allowlist_proof;
+
+    fn entries(n: usize) -> Vec<AllowlistEntry> {
+        (0..n)
+            .map(|i| AllowlistEntry {
+                wallet: Pubkey::new_unique(),
+                cap: i as u64 * 1_000,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_proof_verifies_against_the_root() {
+        for n in [1, 2, 3, 4, 5, 7, 8, 16, 33] {
+            let tree = AllowlistTree::new(entries(n)).unwrap();
+            let root = tree.root();
+            for (i, entry) in tree.entries().iter().enumerate() {
+                let proof = tree.proof(i).unwrap();
+                assert_eq!(proof.cap, entry.cap);
+                let leaf = allowlist_leaf(&entry.wallet, entry.cap);
+                assert!(verify_allowlist_proof(&root, leaf, &proof.proof), "n={n} i={i}");
+                assert_eq!(tree.proof_for(&entry.wallet).unwrap().proof, proof.proof);
+            }
+            assert!(tree.proof(n).is_none());
+        }
+    }
+
+    #[test]
+    fn single_leaf_is_the_root() {
+        let tree = AllowlistTree::new(entries(1)).unwrap();
+        let entry = tree.entries()[0];
+        assert_eq!(tree.root(), allowlist_leaf(&entry.wallet, entry.cap));
+        assert!(tree.proof(0).unwrap().proof.is_empty());
+    }
+
+    #[test]
+    fn proofs_do_not_verify_other_caps_or_wallets() {
+        let tree = AllowlistTree::new(entries(5)).unwrap();
+        let entry = tree.entries()[2];
+        let proof = tree.proof(2).unwrap();
+        let root = tree.root();
+        let wrong_cap = allowlist_leaf(&entry.wallet, entry.cap + 1);
+        assert!(!verify_allowlist_proof(&root, wrong_cap, &proof.proof));
+        let wrong_wallet = allowlist_leaf(&Pubkey::new_unique(), entry.cap);
+        assert!(!verify_allowlist_proof(&root, wrong_wallet, &proof.proof));
+        assert!(tree.proof_for(&Pubkey::new_unique()).is_none());
+    }
+
+    #[test]
+    fn rejects_empty_and_duplicate_input() {
+        assert!(matches!(AllowlistTree::new(Vec::new()), Err(AllowlistError::Empty)));
+
+        let mut list = entries(3);
+        let dup = AllowlistEntry { wallet: list[1].wallet, cap: 5 };
+        list.push(dup);
+        assert!(matches!(
+            AllowlistTree::new(list),
+            Err(AllowlistError::DuplicateWallet(wallet)) if wallet == dup.wallet
+        ));
+    }
+
+    #[test]
+    fn parses_header_comments_and_empty_caps() {
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let csv = format!("wallet,cap\n# early backers\n{a},250\n\n{b}\n{c}, \n");
+        let parsed = read_csv(csv.as_bytes()).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                AllowlistEntry { wallet: a, cap: 250 },
+                AllowlistEntry { wallet: b, cap: 0 },
+                AllowlistEntry { wallet: c, cap: 0 },
+            ]
+        );
+
+        // The header is optional
+        let parsed = read_csv(format!("{a},0\n").as_bytes()).unwrap();
+        assert_eq!(parsed, vec![AllowlistEntry { wallet: a, cap: 0 }]);
+    }
+
+    #[test]
+    fn reports_the_line_of_invalid_rows() {
+        let a = Pubkey::new_unique();
+        let csv = format!("wallet,cap\n{a},10\nnot-a-wallet,5\n");
+        let err = read_csv(csv.as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            AllowlistError::InvalidWallet { line: 3, ref value } if value == "not-a-wallet"
+        ));
+
+        let err = read_csv(format!("{a},-1\n").as_bytes()).unwrap_err();
+        assert!(matches!(err, AllowlistError::InvalidCap { line: 1, ref value } if value == "-1"));
+    }
+}
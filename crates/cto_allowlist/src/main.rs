@@ -0,0 +1,53 @@
+// Usage: cto-allowlist <wallets.csv>
+//
+// Prints the allowlist root and every wallet's proof as JSON. Pass the root as
+// `CampaignOptions::allowlist_root` and each proof as `contribute`'s `allowlist`.
+
+use std::fs::File;
+use std::process::ExitCode;
+
+use cto_allowlist::{read_csv, AllowlistTree};
+use serde_json::json;
+
+fn main() -> ExitCode {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: cto-allowlist <wallets.csv>");
+        return ExitCode::FAILURE;
+    };
+
+    match run(&path) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let entries = read_csv(File::open(path)?)?;
+    let tree = AllowlistTree::new(entries)?;
+
+    let wallets: Vec<_> = tree
+        .entries()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| {
+            let proof = tree.proof(i)?;
+            Some(json!({
+                "wallet": entry.wallet.to_string(),
+                "cap": entry.cap.to_string(),
+                "proof": proof.proof.iter().map(hex::encode).collect::<Vec<_>>(),
+            }))
+        })
+        .collect();
+
+    let output = json!({
+        "root": hex::encode(tree.root()),
+        "wallets": wallets,
+    });
+    Ok(serde_json::to_string_pretty(&output)?)
+}
@@ -1,6 +1,17 @@
+// Anchor's generated IDL handlers still call the deprecated `AccountInfo::realloc`
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+use anchor_spl::associated_token::{
+    self, get_associated_token_address, get_associated_token_address_with_program_id, AssociatedToken,
+};
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::spl_token_2022::state::Mint as Token2022Mint;
+use anchor_spl::token_2022::{self, InitializeMint2, Token2022};
+use anchor_spl::token_interface::{non_transferable_mint_initialize, NonTransferableMintInitialize};
+use sha2::{Digest, Sha256};
 use tiny_keccak::{Hasher, Keccak};
 
 declare_id!("CfzHBxVGRyVC6TythNtmDkXVX1k9iJQvwzBasFDDbLsY");
@@ -11,6 +22,32 @@ pub const MAX_CONTRIBUTION_AMOUNT: u64 = 1_000_000_000_000; // 1M tokens (assumi
 pub const MIN_CAMPAIGN_DURATION: i64 = 3600; // 1 hour in seconds
 pub const MAX_CAMPAIGN_DURATION: i64 = 365 * 24 * 3600; // 1 year in seconds
 pub const MAX_CAMPAIGN_TARGET: u64 = 1_000_000_000_000_000; // 1B tokens (assuming 6 decimals)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_WITHDRAWAL_PENALTY_BPS: u16 = 5_000; // 50%
+pub const MAX_REFERRAL_REWARD_BPS: u16 = 1_000; // 10%
+
+// Contributors can approve or reject submitted metadata for this long
+pub const METADATA_APPROVAL_WINDOW: i64 = 24 * 3600; // 1 day in seconds
+
+// Deepest allowlist proof accepted by `contribute` (trees of up to 2^24 wallets)
+pub const MAX_ALLOWLIST_PROOF_LEN: usize = 24;
+
+// Contribution tiers a campaign may define, and the longest tier label in bytes
+pub const MAX_TIERS: usize = 5;
+pub const MAX_TIER_LABEL_LEN: usize = 32;
+
+// Longest contribution memo in bytes
+pub const MAX_MEMO_LEN: usize = 100;
+
+// Number of contributors ranked on each campaign's leaderboard
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// (Contribution, contributor ATA) pairs per `batch_refund`. A pair costs roughly 18k
+// compute units (two PDA derivations, the refund transfer CPI and the Contribution
+// round trip), so eight fit the 200k units an instruction gets by default next to the
+// fixed accounts (tests/batch_refund_cu.test.ts measures both). They also keep a legacy
+// transaction well under the 1232-byte packet limit, where 13 would be the maximum.
+pub const MAX_BATCH_REFUND: usize = 8;
 
 // Reentrancy protection constants
 pub const REENTRANCY_GUARD_DURATION: i64 = 300; // 5 minutes in seconds
@@ -44,19 +81,114 @@ pub mod cto_dex_escrow {
         ctx: Context<InitCampaign>,
         target_amount: u64,
         deadline_unix: i64,
+        options: CampaignOptions,
     ) -> Result<()> {
         // Enhanced amount validation
         require!(target_amount >= MIN_CONTRIBUTION_AMOUNT, EscrowError::AmountTooSmall);
         require!(target_amount <= MAX_CAMPAIGN_TARGET, EscrowError::AmountTooLarge);
         
-        // Enhanced deadline validation
+        // Campaigns may be announced ahead of time; by default they open immediately
         let now = Clock::get()?.unix_timestamp;
-        require!(deadline_unix > now, EscrowError::InvalidDeadline);
+        let start = options.start_unix.unwrap_or(now);
+        require!(
+            start >= now && start - now <= MAX_CAMPAIGN_DURATION,
+            EscrowError::InvalidStartTime
+        );
+
+        // Enhanced deadline validation, with durations measured from the start
+        require!(deadline_unix > start, EscrowError::InvalidDeadline);
         
-        let duration = deadline_unix - now;
+        let duration = deadline_unix - start;
         require!(duration >= MIN_CAMPAIGN_DURATION, EscrowError::DurationTooShort);
         require!(duration <= MAX_CAMPAIGN_DURATION, EscrowError::DurationTooLong);
 
+        // Resolve the per-contributor cap to an absolute amount (0 = uncapped)
+        let max_per_contributor = match options.contribution_cap {
+            None => 0,
+            Some(ContributionCap::Absolute(cap)) => cap,
+            Some(ContributionCap::PercentOfTargetBps(bps)) => {
+                require!(
+                    bps > 0 && u64::from(bps) <= BPS_DENOMINATOR,
+                    EscrowError::InvalidContributionCap
+                );
+                ((target_amount as u128) * u128::from(bps) / u128::from(BPS_DENOMINATOR)) as u64
+            }
+        };
+        if options.contribution_cap.is_some() {
+            require!(max_per_contributor >= MIN_CONTRIBUTION_AMOUNT, EscrowError::InvalidContributionCap);
+        }
+
+        // Flexible campaigns may keep what they raise once the soft cap is reached
+        let funding_mode = options.funding_mode.unwrap_or_default();
+        let soft_cap = match (funding_mode, options.soft_cap) {
+            (FundingMode::AllOrNothing, None) => target_amount,
+            (FundingMode::Flexible, Some(soft_cap)) => {
+                require!(
+                    (MIN_CONTRIBUTION_AMOUNT..=target_amount).contains(&soft_cap),
+                    EscrowError::InvalidSoftCap
+                );
+                soft_cap
+            }
+            _ => return err!(EscrowError::InvalidSoftCap),
+        };
+
+        // The hard cap bounds what the vault accepts and defaults to the target
+        let hard_cap = options.hard_cap.unwrap_or(target_amount);
+        require!(
+            (target_amount..=MAX_CAMPAIGN_TARGET).contains(&hard_cap),
+            EscrowError::InvalidHardCap
+        );
+
+        // Each backer holds at least MIN_CONTRIBUTION_AMOUNT, which the per-wallet cap must
+        // admit, so the hard cap has to fit that many minimum positions
+        let min_contributors = options.min_contributors.unwrap_or(0);
+        require!(
+            u64::from(min_contributors) * MIN_CONTRIBUTION_AMOUNT <= hard_cap
+                && (max_per_contributor == 0 || max_per_contributor >= MIN_CONTRIBUTION_AMOUNT),
+            EscrowError::InvalidMinContributors
+        );
+
+        // Withdrawal terms: the penalty is bounded and the cutoff must leave a window open
+        let withdrawal_penalty_bps = options.withdrawal_penalty_bps.unwrap_or(0);
+        require!(
+            withdrawal_penalty_bps <= MAX_WITHDRAWAL_PENALTY_BPS,
+            EscrowError::InvalidWithdrawalTerms
+        );
+        let withdrawal_cutoff = options.withdrawal_cutoff_secs.unwrap_or(0);
+        require!(
+            withdrawal_cutoff >= 0 && withdrawal_cutoff < duration,
+            EscrowError::InvalidWithdrawalTerms
+        );
+
+        // Referral rewards are reserved out of the surplus `payout` leaves in the vault
+        let referral_reward_bps = options.referral_reward_bps.unwrap_or(0);
+        require!(
+            referral_reward_bps <= MAX_REFERRAL_REWARD_BPS,
+            EscrowError::InvalidReferralReward
+        );
+
+        // Tiers are listed from the lowest to the highest minimum amount
+        require!(options.tiers.len() <= MAX_TIERS, EscrowError::InvalidTiers);
+        let mut tiers = Vec::with_capacity(options.tiers.len());
+        let mut floor = MIN_CONTRIBUTION_AMOUNT.saturating_sub(1);
+        for tier in options.tiers {
+            require!(
+                tier.min_amount > floor && tier.min_amount <= hard_cap,
+                EscrowError::InvalidTiers
+            );
+            require!(
+                !tier.label.is_empty() && tier.label.len() <= MAX_TIER_LABEL_LEN,
+                EscrowError::InvalidTiers
+            );
+            floor = tier.min_amount;
+            tiers.push(Tier {
+                min_amount: tier.min_amount,
+                label: tier.label,
+                max_slots: tier.max_slots,
+                filled: 0,
+            });
+        }
+
         // Validate campaign creator has sufficient balance for rent
         let rent = Rent::get()?;
         let campaign_rent = rent.minimum_balance(Campaign::SPACE);
@@ -74,48 +206,174 @@ pub mod cto_dex_escrow {
         campaign.bump = ctx.bumps.campaign;
         campaign.target_amount = target_amount;
         campaign.total_contributed = 0;
+        campaign.start_time = start;
         campaign.deadline = deadline_unix;
         campaign.status = CampaignStatus::Pending as u8;
         campaign.top_contributor = Pubkey::default();
         campaign.top_contributor_amount = 0;
+        campaign.metadata_authority = Pubkey::default();
         campaign.metadata_uri = String::new();
         campaign.metadata_hash = [0u8; 32];
+        campaign.metadata_hash_algorithm = MetadataHashAlgorithm::Sha256 as u8;
+        campaign.metadata_version = 0;
+        campaign.metadata_submitted_at = 0;
+        campaign.metadata_approve_weight = 0;
+        campaign.metadata_reject_weight = 0;
         campaign.merchant_hash = [0u8; 32];
         campaign.merchant_hash_set = false;
         campaign.delegate_authority = Pubkey::default();
+        campaign.contributor_count = 0;
+        campaign.min_contributors = min_contributors;
+        campaign.max_per_contributor = max_per_contributor;
+        campaign.allowlist_root = options.allowlist_root.unwrap_or([0u8; 32]);
+        campaign.withdrawal_penalty_bps = withdrawal_penalty_bps;
+        campaign.withdrawal_cutoff = withdrawal_cutoff;
+        campaign.funding_mode = funding_mode as u8;
+        campaign.soft_cap = soft_cap;
+        campaign.hard_cap = hard_cap;
+        campaign.partial_fill = options.partial_fill;
+        campaign.auto_finalize = options.auto_finalize;
+        campaign.extension_requires_consent = options.extension_requires_consent;
+        campaign.deadline_extended = false;
+        campaign.extended_at = 0;
+        campaign.proposed_deadline = 0;
+        campaign.extension_approve_weight = 0;
+        campaign.extension_reject_weight = 0;
+        campaign.merge_target = Pubkey::default();
+        campaign.receipt_mint = Pubkey::default();
+        campaign.tiers = tiers;
+        campaign.referral_reward_bps = referral_reward_bps;
+        campaign.referred_total = 0;
+        campaign.referral_reserve = 0;
         
         // Initialize reentrancy protection
         campaign.reentrancy_guard = false;
         campaign.last_operation_timestamp = 0;
+
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.campaign = campaign.key();
+        leaderboard.bump = ctx.bumps.leaderboard;
         
         Ok(())
     }
 
-    pub fn contribute(ctx: Context<Contribute>, amount: u64) -> Result<()> {
+    // Opts a campaign into receipt tokens before anyone contributes. Deposits then
+    // mint receipts 1:1 and the vault is settled by burning them in `redeem_receipts`.
+    // Receipts carry every right, so `Contribution` is only a deposit record there:
+    // paths that act on its amount (withdrawals, transfers, refunds, votes, badges)
+    // are closed, it ranks no one and assigns no tier (so no contributor is ever
+    // frozen as `metadata_authority`), and per-wallet rules it would have to enforce
+    // (caps, allowlists, tiers, `min_contributors`, extension consent) are refused.
+    pub fn enable_receipts(ctx: Context<EnableReceipts>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        require!(ctx.accounts.creator.key() == campaign.creator, EscrowError::Unauthorized);
+        require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
+        require!(
+            campaign.total_contributed == 0 && campaign.contributor_count == 0,
+            EscrowError::ReceiptsLocked
+        );
+        require!(!campaign.extension_requires_consent, EscrowError::ReceiptCampaign);
+        require!(
+            campaign.max_per_contributor == 0
+                && campaign.min_contributors == 0
+                && campaign.allowlist_root == [0u8; 32]
+                && campaign.tiers.is_empty(),
+            EscrowError::ReceiptCampaign
+        );
+
+        let mut _guard = ReentrancyScope::new(campaign)?;
+        _guard.campaign.receipt_mint = ctx.accounts.receipt_mint.key();
+        Ok(())
+    }
+
+    // Creates the referrer's attribution account for a campaign, so contributors
+    // can name them in `contribute`
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        require!(
+            ctx.accounts.campaign.status == CampaignStatus::Pending as u8,
+            EscrowError::WrongStatus
+        );
+
+        let referral = &mut ctx.accounts.referral;
+        referral.campaign = ctx.accounts.campaign.key();
+        referral.referrer = ctx.accounts.referrer.key();
+        referral.bump = ctx.bumps.referral;
+        referral.referred_amount = 0;
+        referral.referred_count = 0;
+        referral.reward_claimed = false;
+        Ok(())
+    }
+
+    pub fn contribute(
+        ctx: Context<Contribute>,
+        amount: u64,
+        allowlist: Option<AllowlistProof>,
+        referrer: Option<Pubkey>,
+        memo: Option<String>,
+        beneficiary: Option<Pubkey>,
+    ) -> Result<()> {
         let campaign = &mut ctx.accounts.campaign;
 
         // Enhanced amount validation
         require!(amount >= MIN_CONTRIBUTION_AMOUNT, EscrowError::AmountTooSmall);
         require!(amount <= MAX_CONTRIBUTION_AMOUNT, EscrowError::AmountTooLarge);
+        if let Some(memo) = &memo {
+            validate_memo(memo)?;
+        }
         
         let now = Clock::get()?.unix_timestamp;
+        let payer = ctx.accounts.contributor.key();
+        // Tokens come from the signer; the position (and its refund rights) belongs to the beneficiary
+        let beneficiary = beneficiary.unwrap_or(payer);
+
+        // Partial-fill campaigns accept only the remaining capacity instead of failing
+        let remaining = campaign.hard_cap.saturating_sub(campaign.total_contributed);
+        let amount = if amount > remaining && campaign.partial_fill {
+            remaining
+        } else {
+            amount
+        };
+        require!(amount > 0, EscrowError::ExceedsTarget);
 
-        require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
-        require!(now <= campaign.deadline, EscrowError::DeadlinePassed);
-
-        // Validate contributor has sufficient balance
+        // Validate contributor has sufficient balance for what is actually taken
         let contributor_balance = ctx.accounts.contributor_ata.amount;
         require!(contributor_balance >= amount, EscrowError::InsufficientBalance);
 
-        // Validate campaign hasn't exceeded target
-        let new_total = campaign.total_contributed
-            .checked_add(amount)
-            .ok_or(EscrowError::Overflow)?;
-        require!(new_total <= campaign.target_amount, EscrowError::ExceedsTarget);
+        campaign.validate_contribution(
+            &beneficiary,
+            ctx.accounts.contribution.amount,
+            amount,
+            allowlist.as_ref(),
+            now,
+        )?;
 
         // Precompute any values needed while not holding the guard
         let campaign_key = campaign.key();
 
+        // Attribution sticks to the first referrer named for a contribution, so only
+        // the beneficiary may name one; deposits on their behalf follow their choice
+        require!(
+            referrer.is_none() || beneficiary == payer,
+            EscrowError::InvalidReferrer
+        );
+        let sticky = ctx.accounts.contribution.referrer;
+        let referrer = match referrer {
+            Some(named) if sticky != Pubkey::default() && named != sticky => {
+                return err!(EscrowError::InvalidReferrer)
+            }
+            _ if sticky != Pubkey::default() => Some(sticky),
+            named => named,
+        };
+        if let Some(referrer) = referrer {
+            require!(referrer != beneficiary && referrer != payer, EscrowError::InvalidReferrer);
+            let referral = ctx.accounts.referral.as_ref().ok_or(EscrowError::ReferralAccountRequired)?;
+            require!(
+                referral.campaign == campaign_key && referral.referrer == referrer,
+                EscrowError::InvalidReferrer
+            );
+        }
+
         // Set guard only around the mutation/transfer section
         let mut _guard = ReentrancyScope::new(campaign)?;
 
@@ -128,37 +386,82 @@ pub mod cto_dex_escrow {
         let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
         token::transfer(cpi_ctx, amount)?;
 
-        // Update contributor record
-        let contribution = &mut ctx.accounts.contribution;
-        if contribution.amount == 0 {
-            contribution.contributor = ctx.accounts.contributor.key();
-            contribution.campaign = campaign_key;
-            contribution.refunded = false;
+        _guard.campaign.record_contribution(
+            campaign_key,
+            &mut ctx.accounts.contribution,
+            &mut ctx.accounts.leaderboard,
+            beneficiary,
+            amount,
+        )?;
+        // Only the owner's own deposits accept an applied deadline extension;
+        // deposits on someone's behalf must not cost them their free exit
+        if beneficiary == payer {
+            ctx.accounts.contribution.last_deposit_at = now;
         }
-        contribution.amount = contribution
-            .amount
-            .checked_add(amount)
-            .ok_or(EscrowError::Overflow)?;
 
-        // Update campaign totals
-        _guard.campaign.total_contributed = new_total;
+        mint_receipts(
+            _guard.campaign,
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_account.as_ref(),
+            ctx.accounts.token_program.to_account_info(),
+            amount,
+        )?;
+
+        if let (Some(referrer), Some(referral)) = (referrer, ctx.accounts.referral.as_mut()) {
+            let contribution = &mut ctx.accounts.contribution;
+            if contribution.referrer == Pubkey::default() {
+                contribution.referrer = referrer;
+                referral.referred_count = referral
+                    .referred_count
+                    .checked_add(1)
+                    .ok_or(EscrowError::Overflow)?;
+            }
+            referral.referred_amount = referral
+                .referred_amount
+                .checked_add(amount)
+                .ok_or(EscrowError::Overflow)?;
+            _guard.campaign.referred_total = _guard
+                .campaign
+                .referred_total
+                .checked_add(amount)
+                .ok_or(EscrowError::Overflow)?;
+        }
 
-        if contribution.amount > _guard.campaign.top_contributor_amount {
-            _guard.campaign.top_contributor_amount = contribution.amount;
-            _guard.campaign.top_contributor = ctx.accounts.contributor.key();
+        // Every memo is emitted; the beneficiary's own latest memo is kept on the contribution
+        if let Some(memo) = memo {
+            if beneficiary == payer {
+                ctx.accounts.contribution.memo = memo.clone();
+            }
+            emit!(ContributionMemo {
+                campaign: campaign_key,
+                contributor: beneficiary,
+                payer,
+                amount,
+                memo,
+            });
         }
         
         Ok(())
     }
 
-    pub fn submit_metadata(ctx: Context<SubmitMetadata>, uri: String, metadata_hash: [u8; 32]) -> Result<()> {
+    pub fn submit_metadata(
+        ctx: Context<SubmitMetadata>,
+        uri: String,
+        metadata_hash: [u8; 32],
+        hash_algorithm: MetadataHashAlgorithm,
+    ) -> Result<()> {
         let campaign = &mut ctx.accounts.campaign;
 
-        require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
+        require!(
+            campaign.status == CampaignStatus::Pending as u8
+                || campaign.status == CampaignStatus::Succeeded as u8,
+            EscrowError::WrongStatus
+        );
+        require!(!campaign.merchant_hash_set, EscrowError::MerchantHashAlreadySet);
         
-        // Only the top contributor or creator can submit
+        // Only the metadata authority (frozen at finalize) or creator can submit
         let signer = ctx.accounts.submitter.key();
-        require!(signer == campaign.top_contributor || signer == campaign.creator, EscrowError::Unauthorized);
+        require!(campaign.is_metadata_authority(&signer), EscrowError::Unauthorized);
         
         // Enhanced URI validation
         require!(!uri.is_empty(), EscrowError::EmptyUri);
@@ -172,10 +475,80 @@ pub mod cto_dex_escrow {
         // Require a non-zero metadata hash to carry an integrity commitment
         require!(metadata_hash != [0u8; 32], EscrowError::InvalidMetadataHash);
 
+        // A multihash digest only makes sense for content served from IPFS
+        require!(
+            hash_algorithm != MetadataHashAlgorithm::IpfsMultihash || is_ipfs,
+            EscrowError::InvalidHashAlgorithm
+        );
+
         let mut _guard = ReentrancyScope::new(campaign)?;
 
         _guard.campaign.metadata_uri = uri;
         _guard.campaign.metadata_hash = metadata_hash;
+        _guard.campaign.metadata_hash_algorithm = hash_algorithm as u8;
+
+        // Every submission opens a fresh approval round
+        _guard.campaign.metadata_version = _guard
+            .campaign
+            .metadata_version
+            .checked_add(1)
+            .ok_or(EscrowError::Overflow)?;
+        _guard.campaign.metadata_submitted_at = Clock::get()?.unix_timestamp;
+        _guard.campaign.metadata_approve_weight = 0;
+        _guard.campaign.metadata_reject_weight = 0;
+
+        Ok(())
+    }
+
+    // Contributors approve or reject the current metadata, weighted by their contribution
+    pub fn vote_metadata(ctx: Context<VoteMetadata>, approve: bool) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let contribution = &mut ctx.accounts.contribution;
+
+        require!(
+            campaign.status == CampaignStatus::Pending as u8
+                || campaign.status == CampaignStatus::Succeeded as u8,
+            EscrowError::WrongStatus
+        );
+        // Receipts move freely, so `Contribution` amounts are no voting weight there
+        require!(campaign.receipt_mint == Pubkey::default(), EscrowError::ReceiptCampaign);
+        require!(campaign.metadata_version > 0, EscrowError::MetadataNotSubmitted);
+
+        let now = Clock::get()?.unix_timestamp;
+        let window_end = campaign
+            .metadata_submitted_at
+            .checked_add(METADATA_APPROVAL_WINDOW)
+            .ok_or(EscrowError::Overflow)?;
+        require!(now <= window_end, EscrowError::VotingClosed);
+
+        require!(!contribution.refunded && contribution.amount > 0, EscrowError::NothingToVote);
+        require!(
+            contribution.metadata_vote_version != campaign.metadata_version,
+            EscrowError::AlreadyVoted
+        );
+
+        let weight = contribution.amount;
+        let metadata_version = campaign.metadata_version;
+
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        if approve {
+            _guard.campaign.metadata_approve_weight = _guard
+                .campaign
+                .metadata_approve_weight
+                .checked_add(weight)
+                .ok_or(EscrowError::Overflow)?;
+        } else {
+            _guard.campaign.metadata_reject_weight = _guard
+                .campaign
+                .metadata_reject_weight
+                .checked_add(weight)
+                .ok_or(EscrowError::Overflow)?;
+        }
+
+        contribution.metadata_vote_version = metadata_version;
+        contribution.metadata_vote_approve = approve;
+        contribution.metadata_vote_weight = weight;
 
         Ok(())
     }
@@ -186,12 +559,16 @@ pub mod cto_dex_escrow {
         require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
         let now = Clock::get()?.unix_timestamp;
 
-        if campaign.total_contributed >= campaign.target_amount {
+        if campaign.is_full() {
             let mut _guard = ReentrancyScope::new(campaign)?;
-            _guard.campaign.status = CampaignStatus::Succeeded as u8;
+            _guard.campaign.mark_succeeded();
         } else if now > campaign.deadline {
             let mut _guard = ReentrancyScope::new(campaign)?;
-            _guard.campaign.status = CampaignStatus::Failed as u8;
+            if _guard.campaign.soft_cap_met() {
+                _guard.campaign.mark_succeeded();
+            } else {
+                _guard.campaign.status = CampaignStatus::Failed as u8;
+            }
         } else {
             return err!(EscrowError::GoalNotMet);
         }
@@ -200,29 +577,162 @@ pub mod cto_dex_escrow {
     }
 
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        // Copy the fields we need before mutable borrow
-        let creator = ctx.accounts.campaign.creator;
-        let bump = ctx.accounts.campaign.bump;
-        let status = ctx.accounts.campaign.status;
+        process_refund(
+            &mut ctx.accounts.campaign,
+            &mut ctx.accounts.contribution,
+            &mut ctx.accounts.leaderboard,
+            &mut ctx.accounts.vault,
+            ctx.accounts.contributor_ata.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        )
+    }
+
+    // Permissionless push refund: anyone (e.g. a keeper) can send a failed
+    // campaign's contribution to the refund recipient's ATA, paying for it if missing
+    pub fn refund_for(ctx: Context<RefundFor>) -> Result<()> {
+        process_refund(
+            &mut ctx.accounts.campaign,
+            &mut ctx.accounts.contribution,
+            &mut ctx.accounts.leaderboard,
+            &mut ctx.accounts.vault,
+            ctx.accounts.recipient_ata.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        )
+    }
 
-        require!(status == CampaignStatus::Failed as u8, EscrowError::WrongStatus);
+    // Refunds several contributions of a failed campaign in one transaction.
+    // `remaining_accounts` holds (Contribution, contributor ATA) pairs; entries
+    // that are already refunded are skipped so keepers can safely resubmit.
+    pub fn batch_refund<'info>(ctx: Context<'_, '_, 'info, 'info, BatchRefund<'info>>) -> Result<()> {
+        let entries = ctx.remaining_accounts;
+        require!(
+            !entries.is_empty() && entries.len() % 2 == 0 && entries.len() / 2 <= MAX_BATCH_REFUND,
+            EscrowError::InvalidBatch
+        );
 
-        let contribution = &mut ctx.accounts.contribution;
+        let campaign_key = ctx.accounts.campaign.key();
+        let pay_mint = ctx.accounts.pay_mint.key();
+
+        for pair in entries.chunks_exact(2) {
+            let (contribution_info, recipient_info) = (&pair[0], &pair[1]);
+
+            let mut contribution = Account::<Contribution>::try_from(contribution_info)?;
+            let (expected, _) = Pubkey::find_program_address(
+                &[b"contribution", campaign_key.as_ref(), contribution.contributor.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(contribution_info.key(), expected, EscrowError::InvalidContributionAccount);
+
+            if contribution.refunded || contribution.amount == 0 {
+                continue;
+            }
+
+            let recipient = get_associated_token_address(&contribution.refund_owner(), &pay_mint);
+            require_keys_eq!(recipient_info.key(), recipient, EscrowError::InvalidRefundAccount);
+
+            process_refund(
+                &mut ctx.accounts.campaign,
+                &mut contribution,
+                &mut ctx.accounts.leaderboard,
+                &mut ctx.accounts.vault,
+                recipient_info.clone(),
+                ctx.accounts.token_program.to_account_info(),
+            )?;
+            contribution.exit(ctx.program_id)?;
+        }
+
+        Ok(())
+    }
+
+    // Settles receipt campaigns: burning receipts pays out their pro-rata share of the
+    // vault, i.e. the deposits of a failed campaign or whatever payout left behind
+    pub fn redeem_receipts(ctx: Context<RedeemReceipts>, amount: u64) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let creator = campaign.creator;
+        let bump = campaign.bump;
+
+        require!(
+            campaign.status == CampaignStatus::Failed as u8 || campaign.status == CampaignStatus::Paid as u8,
+            EscrowError::WrongStatus
+        );
+        require!(amount > 0, EscrowError::InvalidAmount);
+        require!(ctx.accounts.holder_receipt_account.amount >= amount, EscrowError::InsufficientBalance);
+
+        // Unclaimed referral rewards are not part of what holders share
+        let supply = ctx.accounts.receipt_mint.supply;
+        let available = ctx.accounts.vault.amount.saturating_sub(campaign.referral_reserve);
+        let share = ((amount as u128) * (available as u128) / (supply as u128)) as u64;
+
+        let campaign = &mut ctx.accounts.campaign;
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            from: ctx.accounts.holder_receipt_account.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::burn(cpi_ctx, amount)?;
+
+        if share > 0 {
+            let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]];
+            let signer_seeds = &[seeds];
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: _guard.campaign.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, share)?;
+        }
+
+        Ok(())
+    }
+
+    // Lets a contributor exit (fully or partially) while the campaign is still open
+    pub fn withdraw_contribution(ctx: Context<WithdrawContribution>, amount: u64) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let creator = campaign.creator;
+        let bump = campaign.bump;
+
+        require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
+        // Receipt holders exit by selling their receipts
+        require!(campaign.receipt_mint == Pubkey::default(), EscrowError::ReceiptCampaign);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= campaign.deadline, EscrowError::DeadlinePassed);
+
+        let contribution = &ctx.accounts.contribution;
         require!(!contribution.refunded, EscrowError::AlreadyRefunded);
-        let amount = contribution.amount;
-        require!(amount > 0, EscrowError::NothingToRefund);
+        require!(amount > 0, EscrowError::InvalidAmount);
+        require!(amount <= contribution.amount, EscrowError::InsufficientBalance);
+        require!(ctx.accounts.vault.amount >= amount, EscrowError::InsufficientVaultBalance);
+
+        // Contributors who held their position under the original deadline and did
+        // not approve the extension may leave freely; their own deposits after it
+        // applied accepted the new deadline. Deposits by others and transfers into
+        // the position don't count, so a third party cannot take the exit away.
+        let free_exit = campaign.deadline_extended
+            && contribution.extension_vote != ExtensionVote::Approve as u8
+            && contribution.last_deposit_at < campaign.extended_at;
+        require!(
+            free_exit || now <= campaign.deadline.saturating_sub(campaign.withdrawal_cutoff),
+            EscrowError::WithdrawalCutoff
+        );
 
-        // Validate vault has sufficient balance for refund
-        let vault_balance = ctx.accounts.vault.amount;
-        require!(vault_balance >= amount, EscrowError::InsufficientVaultBalance);
+        let penalty_bps = if free_exit { 0 } else { campaign.withdrawal_penalty_bps };
+        let penalty = ((amount as u128) * u128::from(penalty_bps)
+            / u128::from(BPS_DENOMINATOR)) as u64;
+        let returned = amount - penalty;
 
         let campaign = &mut ctx.accounts.campaign;
         let mut _guard = ReentrancyScope::new(campaign)?;
 
-        // Seeds for vault authority = campaign PDA itself
-        let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]]; // campaign PDA seeds
+        let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]];
         let signer_seeds = &[seeds];
-        // Transfer back to contributor
         let cpi_accounts = Transfer {
             from: ctx.accounts.vault.to_account_info(),
             to: ctx.accounts.contributor_ata.to_account_info(),
@@ -233,87 +743,192 @@ pub mod cto_dex_escrow {
             cpi_accounts,
             signer_seeds,
         );
-        token::transfer(cpi_ctx, amount)?;
+        token::transfer(cpi_ctx, returned)?;
+
+        // The penalty goes to the creator who configured it
+        if penalty > 0 {
+            let creator_ata = ctx.accounts.creator_ata.as_ref().ok_or(EscrowError::MissingCreatorAccount)?;
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: creator_ata.to_account_info(),
+                authority: _guard.campaign.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, penalty)?;
+        }
+
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.amount -= amount;
+        _guard.campaign.total_contributed = _guard
+            .campaign
+            .total_contributed
+            .checked_sub(amount)
+            .ok_or(EscrowError::Overflow)?;
+        if contribution.amount == 0 {
+            _guard.campaign.contributor_count = _guard.campaign.contributor_count.saturating_sub(1);
+        }
+        _guard.campaign.sync_votes(contribution)?;
+        _guard.campaign.assign_tier(contribution);
+
+        // Withdrawn funds no longer count toward the referrer's reward
+        if contribution.referrer != Pubkey::default() {
+            let referral = ctx.accounts.referral.as_mut().ok_or(EscrowError::ReferralAccountRequired)?;
+            require!(
+                referral.campaign == _guard.campaign.key() && referral.referrer == contribution.referrer,
+                EscrowError::InvalidReferrer
+            );
+            referral.referred_amount = referral.referred_amount.saturating_sub(amount);
+            _guard.campaign.referred_total = _guard.campaign.referred_total.saturating_sub(amount);
+        }
 
-        contribution.refunded = true;
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.record(contribution.contributor, contribution.amount)?;
+        _guard.campaign.sync_top_contributor(leaderboard);
 
         Ok(())
     }
 
-    pub fn set_merchant_hash(ctx: Context<SetMerchantHash>, merchant_hash: [u8; 32]) -> Result<()> {
+    // The creator may push the deadline out once. With consent required this only
+    // proposes the new deadline until contributors holding a majority approve it.
+    pub fn extend_deadline(ctx: Context<ExtendDeadline>, new_deadline: i64) -> Result<()> {
         let campaign = &mut ctx.accounts.campaign;
 
-        require!(campaign.status == CampaignStatus::Succeeded as u8, EscrowError::WrongStatus);
-        let signer = ctx.accounts.authority.key();
+        require!(ctx.accounts.creator.key() == campaign.creator, EscrowError::Unauthorized);
+        require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
         require!(
-            signer == campaign.top_contributor || signer == campaign.creator || signer == campaign.delegate_authority,
-            EscrowError::Unauthorized
+            !campaign.deadline_extended && campaign.proposed_deadline == 0,
+            EscrowError::DeadlineAlreadyExtended
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= campaign.deadline, EscrowError::DeadlinePassed);
+        require!(new_deadline > campaign.deadline, EscrowError::InvalidDeadline);
+        require!(
+            new_deadline - campaign.start_time <= MAX_CAMPAIGN_DURATION,
+            EscrowError::DurationTooLong
         );
-        
-        // Validate merchant hash is not all zeros
-        require!(merchant_hash != [0u8; 32], EscrowError::InvalidMerchantHash);
 
         let mut _guard = ReentrancyScope::new(campaign)?;
 
-        _guard.campaign.merchant_hash = merchant_hash;
-        _guard.campaign.merchant_hash_set = true;
+        if _guard.campaign.extension_requires_consent {
+            _guard.campaign.proposed_deadline = new_deadline;
+        } else {
+            _guard.campaign.deadline = new_deadline;
+            _guard.campaign.deadline_extended = true;
+            _guard.campaign.extended_at = now;
+        }
 
         Ok(())
     }
 
-    // Allow the creator to set an optional delegate authority
-    pub fn set_delegate_authority(ctx: Context<SetDelegateAuthority>, delegate: Pubkey) -> Result<()> {
+    // Contributors approve a proposed extension or opt out of it. Opting out (like
+    // not voting) keeps the right to withdraw without penalty if it passes anyway.
+    pub fn vote_deadline_extension(ctx: Context<VoteDeadlineExtension>, approve: bool) -> Result<()> {
         let campaign = &mut ctx.accounts.campaign;
-        require!(ctx.accounts.creator.key() == campaign.creator, EscrowError::Unauthorized);
+        let contribution = &mut ctx.accounts.contribution;
+
+        require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
+        require!(campaign.receipt_mint == Pubkey::default(), EscrowError::ReceiptCampaign);
+        require!(
+            campaign.proposed_deadline != 0 && !campaign.deadline_extended,
+            EscrowError::NoExtensionProposed
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= campaign.deadline, EscrowError::VotingClosed);
+
+        require!(!contribution.refunded && contribution.amount > 0, EscrowError::NothingToVote);
+        require!(
+            contribution.extension_vote == ExtensionVote::None as u8,
+            EscrowError::AlreadyVoted
+        );
+
+        let weight = contribution.amount;
+
         let mut _guard = ReentrancyScope::new(campaign)?;
-        _guard.campaign.delegate_authority = delegate;
+
+        if approve {
+            _guard.campaign.extension_approve_weight = _guard
+                .campaign
+                .extension_approve_weight
+                .checked_add(weight)
+                .ok_or(EscrowError::Overflow)?;
+            contribution.extension_vote = ExtensionVote::Approve as u8;
+        } else {
+            _guard.campaign.extension_reject_weight = _guard
+                .campaign
+                .extension_reject_weight
+                .checked_add(weight)
+                .ok_or(EscrowError::Overflow)?;
+            contribution.extension_vote = ExtensionVote::OptOut as u8;
+        }
+        contribution.extension_vote_weight = weight;
+
+        // A majority of `total_contributed` approving applies the extension
+        if (_guard.campaign.extension_approve_weight as u128) * 2 > _guard.campaign.total_contributed as u128 {
+            _guard.campaign.deadline = _guard.campaign.proposed_deadline;
+            _guard.campaign.deadline_extended = true;
+            _guard.campaign.extended_at = now;
+        }
+
         Ok(())
     }
 
-    pub fn payout(ctx: Context<Payout>, amount: u64) -> Result<()> {
-        let campaign = &mut ctx.accounts.campaign;
-
-        // Copy the fields we need before mutable borrow
-        let creator = campaign.creator;
-        let pay_mint = campaign.pay_mint;
-        let bump = campaign.bump;
-        let status = campaign.status;
-        let merchant_hash_set = campaign.merchant_hash_set;
-        let merchant_hash = campaign.merchant_hash;
-        
-        require!(status == CampaignStatus::Succeeded as u8, EscrowError::WrongStatus);
-        require!(merchant_hash_set, EscrowError::MerchantHashNotSet);
-        require!(amount > 0, EscrowError::InvalidAmount);
-        require!(amount >= MIN_CONTRIBUTION_AMOUNT, EscrowError::AmountTooSmall);
-        require!(amount <= MAX_CONTRIBUTION_AMOUNT, EscrowError::AmountTooLarge);
+    // Moves a refundable contribution from a failed campaign straight into a
+    // pending campaign for the same pay mint, instead of refund + contribute
+    pub fn migrate_contribution(
+        ctx: Context<MigrateContribution>,
+        allowlist: Option<AllowlistProof>,
+    ) -> Result<()> {
+        let source = &ctx.accounts.source_campaign;
+        let source_creator = source.creator;
+        let source_bump = source.bump;
+        require!(source.status == CampaignStatus::Failed as u8, EscrowError::WrongStatus);
 
-        // Validate vault has sufficient balance for payout
-        let vault_balance = ctx.accounts.vault.amount;
-        require!(vault_balance >= amount, EscrowError::InsufficientVaultBalance);
+        // Receipt holders, not `Contribution` records, own receipt campaigns' funds
+        require!(
+            source.receipt_mint == Pubkey::default() && ctx.accounts.campaign.receipt_mint == Pubkey::default(),
+            EscrowError::ReceiptCampaign
+        );
 
-        // Validate payout amount doesn't exceed campaign total
-        require!(amount <= campaign.total_contributed, EscrowError::ExceedsCampaignTotal);
+        let source_contribution = &ctx.accounts.source_contribution;
+        require!(!source_contribution.refunded, EscrowError::AlreadyRefunded);
+        // A registered recovery address is where this position's funds go; moving
+        // them to another campaign would let the signer withdraw them instead
+        require!(
+            source_contribution.refund_recipient == Pubkey::default(),
+            EscrowError::RefundRecipientSet
+        );
+        let amount = source_contribution.amount;
+        require!(amount > 0, EscrowError::NothingToRefund);
+        require!(ctx.accounts.source_vault.amount >= amount, EscrowError::InsufficientVaultBalance);
 
-        // Compute keccak256(pay_mint || dest_ata || amount_le)
-        let mut input: Vec<u8> = Vec::with_capacity(32 + 32 + 8);
-        input.extend_from_slice(pay_mint.as_ref());
-        input.extend_from_slice(ctx.accounts.merchant_ata.key().as_ref());
-        input.extend_from_slice(&amount.to_le_bytes());
-        let mut hasher = Keccak::v256();
-        hasher.update(&input);
-        let mut output = [0u8; 32];
-        hasher.finalize(&mut output);
-        require!(output == merchant_hash, EscrowError::MerchantHashMismatch);
+        let now = Clock::get()?.unix_timestamp;
+        let contributor = ctx.accounts.contributor.key();
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.validate_contribution(
+            &contributor,
+            ctx.accounts.contribution.amount,
+            amount,
+            allowlist.as_ref(),
+            now,
+        )?;
+        let campaign_key = campaign.key();
 
+        let source = &mut ctx.accounts.source_campaign;
+        let mut _source_guard = ReentrancyScope::new(source)?;
         let mut _guard = ReentrancyScope::new(campaign)?;
 
-        // transfer to merchant
-        let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]];
+        // Vault to vault, signed by the failed campaign's PDA
+        let seeds: &[&[u8]] = &[b"campaign", source_creator.as_ref(), &[source_bump]];
         let signer_seeds = &[seeds];
         let cpi_accounts = Transfer {
-            from: ctx.accounts.vault.to_account_info(),
-            to: ctx.accounts.merchant_ata.to_account_info(),
-            authority: _guard.campaign.to_account_info(),
+            from: ctx.accounts.source_vault.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: _source_guard.campaign.to_account_info(),
         };
         let cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
@@ -322,31 +937,538 @@ pub mod cto_dex_escrow {
         );
         token::transfer(cpi_ctx, amount)?;
 
-        // Update campaign status
-        _guard.campaign.status = CampaignStatus::Paid as u8;
+        // The old position is settled exactly as a refund would settle it
+        let source_contribution = &mut ctx.accounts.source_contribution;
+        source_contribution.refunded = true;
+        let source_leaderboard = &mut ctx.accounts.source_leaderboard;
+        source_leaderboard.remove(&contributor);
+        _source_guard.campaign.sync_top_contributor(source_leaderboard);
+
+        _guard.campaign.record_contribution(
+            campaign_key,
+            &mut ctx.accounts.contribution,
+            &mut ctx.accounts.leaderboard,
+            contributor,
+            amount,
+        )?;
+        ctx.accounts.contribution.last_deposit_at = now;
 
         Ok(())
     }
 
-    // Emergency function to clear stuck reentrancy guards
-    pub fn clear_reentrancy_guard(ctx: Context<ClearReentrancyGuard>) -> Result<()> {
+    // First half of a merge: the source creator names the campaign to fold into
+    // (or clears the proposal with the default pubkey)
+    pub fn propose_merge(ctx: Context<ProposeMerge>, destination: Pubkey) -> Result<()> {
         let campaign = &mut ctx.accounts.campaign;
-        let now = Clock::get()?.unix_timestamp;
-        
-        // Only allow clearing if guard has been stuck for too long
-        require!(
-            campaign.reentrancy_guard && 
-            (now - campaign.last_operation_timestamp) > REENTRANCY_GUARD_DURATION,
-            EscrowError::CannotClearGuard
+        require!(ctx.accounts.creator.key() == campaign.creator, EscrowError::Unauthorized);
+        require!(campaign.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
+        require!(destination != campaign.key(), EscrowError::InvalidMergeTarget);
+
+        let mut _guard = ReentrancyScope::new(campaign)?;
+        _guard.campaign.merge_target = destination;
+        Ok(())
+    }
+
+    // Second half: the destination creator accepts, the source vault moves over and
+    // the source becomes `Merged`. Its contributors later claim their balances.
+    pub fn accept_merge(ctx: Context<AcceptMerge>) -> Result<()> {
+        let source = &ctx.accounts.source_campaign;
+        let source_creator = source.creator;
+        let source_bump = source.bump;
+        let amount = source.total_contributed;
+
+        let campaign = &ctx.accounts.campaign;
+        require!(ctx.accounts.creator.key() == campaign.creator, EscrowError::Unauthorized);
+        require!(source.merge_target == campaign.key(), EscrowError::InvalidMergeTarget);
+        require!(
+            source.receipt_mint == Pubkey::default() && campaign.receipt_mint == Pubkey::default(),
+            EscrowError::ReceiptCampaign
         );
-        
-        // Only campaign creator or top contributor can clear stuck guards
+        require!(
+            source.status == CampaignStatus::Pending as u8 && campaign.status == CampaignStatus::Pending as u8,
+            EscrowError::WrongStatus
+        );
+        // A source past its deadline owes its contributors a finalize, not a merge
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now <= campaign.deadline && now <= source.deadline,
+            EscrowError::DeadlinePassed
+        );
+
+        let new_total = campaign
+            .total_contributed
+            .checked_add(amount)
+            .ok_or(EscrowError::Overflow)?;
+        require!(new_total <= campaign.hard_cap, EscrowError::ExceedsTarget);
+        require!(ctx.accounts.source_vault.amount >= amount, EscrowError::InsufficientVaultBalance);
+
+        let source = &mut ctx.accounts.source_campaign;
+        let campaign = &mut ctx.accounts.campaign;
+        let mut _source_guard = ReentrancyScope::new(source)?;
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        let seeds: &[&[u8]] = &[b"campaign", source_creator.as_ref(), &[source_bump]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.source_vault.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: _source_guard.campaign.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        _source_guard.campaign.status = CampaignStatus::Merged as u8;
+
+        // Merged funds count toward the destination immediately; distinct backers
+        // are counted as they claim
+        _guard.campaign.total_contributed = new_total;
+        if _guard.campaign.auto_finalize && _guard.campaign.is_full() {
+            _guard.campaign.mark_succeeded();
+        }
+
+        Ok(())
+    }
+
+    // Carries a contributor's balance from a merged source campaign into the
+    // destination. Caps and allowlists do not apply: both creators agreed to the merge.
+    // A destination that has since failed refunds the balance instead.
+    pub fn claim_merged_contribution(ctx: Context<ClaimMergedContribution>) -> Result<()> {
+        let source = &ctx.accounts.source_campaign;
+        require!(source.status == CampaignStatus::Merged as u8, EscrowError::WrongStatus);
+
+        let source_contribution = &ctx.accounts.source_contribution;
+        require!(!source_contribution.refunded, EscrowError::AlreadyRefunded);
+        let amount = source_contribution.amount;
+        require!(amount > 0, EscrowError::NothingToRefund);
+
+        let contributor = ctx.accounts.contributor.key();
+        let campaign = &mut ctx.accounts.campaign;
+        let campaign_key = campaign.key();
+        let creator = campaign.creator;
+        let bump = campaign.bump;
+
+        if campaign.status == CampaignStatus::Failed as u8 {
+            // The destination position may already be refunded or migrated, so the
+            // claimed balance is paid out directly rather than credited to it
+            let refund_ata = ctx.accounts.refund_ata.as_ref().ok_or(EscrowError::InvalidRefundAccount)?;
+            require!(
+                refund_ata.owner == source_contribution.refund_owner(),
+                EscrowError::InvalidRefundAccount
+            );
+            require!(ctx.accounts.vault.amount >= amount, EscrowError::InsufficientVaultBalance);
+
+            let _guard = ReentrancyScope::new(campaign)?;
+            let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]];
+            let signer_seeds = &[seeds];
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: refund_ata.to_account_info(),
+                authority: _guard.campaign.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, amount)?;
+
+            ctx.accounts.source_contribution.refunded = true;
+            return Ok(());
+        }
+
+        // A settled position keeps `refunded` set, which would strand the claimed funds
+        require!(!ctx.accounts.contribution.refunded, EscrowError::AlreadyRefunded);
+        // The source's recovery address follows the balance into the destination
+        let refund_recipient = ctx.accounts.source_contribution.refund_recipient;
+        if refund_recipient != Pubkey::default() {
+            let existing = ctx.accounts.contribution.refund_recipient;
+            require!(
+                existing == Pubkey::default() || existing == refund_recipient,
+                EscrowError::RefundRecipientMismatch
+            );
+            ctx.accounts.contribution.refund_recipient = refund_recipient;
+        }
+
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        ctx.accounts.source_contribution.refunded = true;
+        _guard.campaign.credit_contribution(
+            campaign_key,
+            &mut ctx.accounts.contribution,
+            &mut ctx.accounts.leaderboard,
+            contributor,
+            amount,
+        )?;
+        ctx.accounts.contribution.last_deposit_at = Clock::get()?.unix_timestamp;
+
+        // Claims add distinct backers, which can be what makes the campaign full
+        if _guard.campaign.status == CampaignStatus::Pending as u8
+            && _guard.campaign.auto_finalize
+            && _guard.campaign.is_full()
+        {
+            _guard.campaign.mark_succeeded();
+        }
+
+        Ok(())
+    }
+
+    // Reassigns a position to another wallet (e.g. when a team rotates keys),
+    // merging into the new owner's existing contribution if there is one. Votes
+    // cast by the old position are released and its account is closed. Like
+    // `set_refund_recipient` this is only possible while the campaign is pending,
+    // so refund destinations and the frozen metadata role cannot move afterwards.
+    pub fn transfer_contribution(
+        ctx: Context<TransferContribution>,
+        allowlist: Option<AllowlistProof>,
+    ) -> Result<()> {
+        let contributor = ctx.accounts.contributor.key();
+        let new_owner = ctx.accounts.new_owner.key();
+        require!(new_owner != contributor, EscrowError::InvalidTransferTarget);
+        require!(
+            ctx.accounts.campaign.status == CampaignStatus::Pending as u8,
+            EscrowError::WrongStatus
+        );
+        // Positions in receipt campaigns are the receipts themselves
+        require!(
+            ctx.accounts.campaign.receipt_mint == Pubkey::default(),
+            EscrowError::ReceiptCampaign
+        );
+
+        let contribution = &ctx.accounts.contribution;
+        require!(!contribution.refunded, EscrowError::AlreadyRefunded);
+        let amount = contribution.amount;
+        require!(amount > 0, EscrowError::NothingToRefund);
+        require!(!ctx.accounts.new_contribution.refunded, EscrowError::AlreadyRefunded);
+        // Referral credit moves with the position, so it can only join a position
+        // attributed to the same referrer
+        let referrer = contribution.referrer;
+        require!(
+            ctx.accounts.new_contribution.amount == 0 || ctx.accounts.new_contribution.referrer == referrer,
+            EscrowError::InvalidReferrer
+        );
+
+        let campaign = &mut ctx.accounts.campaign;
+        let new_amount = ctx
+            .accounts
+            .new_contribution
+            .amount
+            .checked_add(amount)
+            .ok_or(EscrowError::Overflow)?;
+        // The new owner must fit the per-wallet limits
+        campaign.validate_position(&new_owner, new_amount, allowlist.as_ref())?;
+        let campaign_key = campaign.key();
+
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.amount = 0;
+        _guard.campaign.sync_votes(contribution)?;
+        _guard.campaign.assign_tier(contribution);
+        _guard.campaign.contributor_count = _guard.campaign.contributor_count.saturating_sub(1);
+
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.remove(&contributor);
+        _guard.campaign.credit_contribution(
+            campaign_key,
+            &mut ctx.accounts.new_contribution,
+            leaderboard,
+            new_owner,
+            amount,
+        )?;
+        ctx.accounts.new_contribution.referrer = referrer;
+
+        Ok(())
+    }
+
+    // Registers where refunds of this contribution go, e.g. a fresh wallet when the
+    // contributor's own is compromised or its token account frozen. The default
+    // pubkey resets it to the contributor.
+    pub fn set_refund_recipient(ctx: Context<SetRefundRecipient>, recipient: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.campaign.status == CampaignStatus::Pending as u8,
+            EscrowError::WrongStatus
+        );
+        ctx.accounts.contribution.refund_recipient = recipient;
+        Ok(())
+    }
+
+    pub fn set_merchant_hash(ctx: Context<SetMerchantHash>, merchant_hash: [u8; 32]) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        require!(campaign.status == CampaignStatus::Succeeded as u8, EscrowError::WrongStatus);
         let signer = ctx.accounts.authority.key();
         require!(
-            signer == campaign.creator || signer == campaign.top_contributor,
+            campaign.is_metadata_authority(&signer) || signer == campaign.delegate_authority,
             EscrowError::Unauthorized
         );
         
+        // Validate merchant hash is not all zeros
+        require!(merchant_hash != [0u8; 32], EscrowError::InvalidMerchantHash);
+
+        // Contributors must have signed off on what is being submitted
+        let now = Clock::get()?.unix_timestamp;
+        require!(campaign.metadata_approved(now), EscrowError::MetadataNotApproved);
+
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        _guard.campaign.merchant_hash = merchant_hash;
+        _guard.campaign.merchant_hash_set = true;
+
+        Ok(())
+    }
+
+    // Allow the creator to set an optional delegate authority
+    pub fn set_delegate_authority(ctx: Context<SetDelegateAuthority>, delegate: Pubkey) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        require!(ctx.accounts.creator.key() == campaign.creator, EscrowError::Unauthorized);
+        let mut _guard = ReentrancyScope::new(campaign)?;
+        _guard.campaign.delegate_authority = delegate;
+        Ok(())
+    }
+
+    pub fn payout(ctx: Context<Payout>, amount: u64) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+
+        // Copy the fields we need before mutable borrow
+        let creator = campaign.creator;
+        let pay_mint = campaign.pay_mint;
+        let bump = campaign.bump;
+        let status = campaign.status;
+        let merchant_hash_set = campaign.merchant_hash_set;
+        let merchant_hash = campaign.merchant_hash;
+        
+        require!(status == CampaignStatus::Succeeded as u8, EscrowError::WrongStatus);
+        require!(merchant_hash_set, EscrowError::MerchantHashNotSet);
+        require!(amount > 0, EscrowError::InvalidAmount);
+        require!(amount >= MIN_CONTRIBUTION_AMOUNT, EscrowError::AmountTooSmall);
+        require!(amount <= MAX_CONTRIBUTION_AMOUNT, EscrowError::AmountTooLarge);
+
+        // Validate vault has sufficient balance for payout
+        let vault_balance = ctx.accounts.vault.amount;
+        require!(vault_balance >= amount, EscrowError::InsufficientVaultBalance);
+
+        // Validate payout amount doesn't exceed campaign total
+        require!(amount <= campaign.total_contributed, EscrowError::ExceedsCampaignTotal);
+
+        // Referral rewards stay behind in the vault
+        let referral_reserve = ((campaign.referred_total as u128) * u128::from(campaign.referral_reward_bps)
+            / u128::from(BPS_DENOMINATOR)) as u64;
+        require!(
+            vault_balance - amount >= referral_reserve,
+            EscrowError::ReferralRewardsUnfunded
+        );
+
+        // Compute keccak256(pay_mint || dest_ata || amount_le)
+        let mut input: Vec<u8> = Vec::with_capacity(32 + 32 + 8);
+        input.extend_from_slice(pay_mint.as_ref());
+        input.extend_from_slice(ctx.accounts.merchant_ata.key().as_ref());
+        input.extend_from_slice(&amount.to_le_bytes());
+        let mut hasher = Keccak::v256();
+        hasher.update(&input);
+        let mut output = [0u8; 32];
+        hasher.finalize(&mut output);
+        require!(output == merchant_hash, EscrowError::MerchantHashMismatch);
+
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        // transfer to merchant
+        let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.merchant_ata.to_account_info(),
+            authority: _guard.campaign.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+
+        // Update campaign status
+        _guard.campaign.status = CampaignStatus::Paid as u8;
+        _guard.campaign.referral_reserve = referral_reserve;
+
+        Ok(())
+    }
+
+    // Pays a referrer `referral_reward_bps` of the amount they referred, out of
+    // the reserve `payout` left in the vault
+    pub fn claim_referral_reward(ctx: Context<ClaimReferralReward>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let creator = campaign.creator;
+        let bump = campaign.bump;
+        require!(campaign.status == CampaignStatus::Paid as u8, EscrowError::WrongStatus);
+
+        let referral = &ctx.accounts.referral;
+        require!(!referral.reward_claimed, EscrowError::RewardAlreadyClaimed);
+        let reward = ((referral.referred_amount as u128) * u128::from(campaign.referral_reward_bps)
+            / u128::from(BPS_DENOMINATOR)) as u64;
+        require!(reward > 0, EscrowError::NothingToClaim);
+        require!(
+            reward <= campaign.referral_reserve && ctx.accounts.vault.amount >= reward,
+            EscrowError::InsufficientVaultBalance
+        );
+
+        let campaign = &mut ctx.accounts.campaign;
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.referrer_ata.to_account_info(),
+            authority: _guard.campaign.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, reward)?;
+
+        ctx.accounts.referral.reward_claimed = true;
+        _guard.campaign.referral_reserve -= reward;
+
+        Ok(())
+    }
+
+    // Mints the contributor a soulbound (Token-2022 NonTransferable) badge for a paid
+    // campaign: a decimals-0 mint per `Contribution` with the campaign PDA as mint
+    // authority, holding a single token in the contributor's ATA.
+    pub fn mint_badge(ctx: Context<MintBadge>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let creator = campaign.creator;
+        let bump = campaign.bump;
+        require!(campaign.status == CampaignStatus::Paid as u8, EscrowError::WrongStatus);
+        // Deposits in receipt campaigns don't show who still backs it
+        require!(campaign.receipt_mint == Pubkey::default(), EscrowError::ReceiptCampaign);
+
+        let contribution = &ctx.accounts.contribution;
+        require!(!contribution.refunded && contribution.amount > 0, EscrowError::NotEligibleForBadge);
+        // Positions cannot move once the campaign is paid (see `transfer_contribution`),
+        // so this flag is the only badge a position can ever have
+        require!(!contribution.badge_minted, EscrowError::BadgeAlreadyMinted);
+
+        let contributor = ctx.accounts.contributor.key();
+        let rank = if campaign.top_contributor == contributor {
+            BadgeRank::TopContributor
+        } else if ctx.accounts.leaderboard.entries().iter().any(|e| e.contributor == contributor) {
+            BadgeRank::Leaderboard
+        } else {
+            BadgeRank::Backer
+        };
+
+        let contribution_key = contribution.key();
+        let badge_seeds: &[&[u8]] = &[b"badge", contribution_key.as_ref(), &[ctx.bumps.badge_mint]];
+        let space = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[ExtensionType::NonTransferable])?;
+        let lamports = Rent::get()?.minimum_balance(space);
+        let token_program = ctx.accounts.token_2022_program.to_account_info();
+
+        let campaign = &mut ctx.accounts.campaign;
+        let mut _guard = ReentrancyScope::new(campaign)?;
+
+        let badge_signer = &[badge_seeds];
+        let badge_mint = ctx.accounts.badge_mint.to_account_info();
+        let system = ctx.accounts.system_program.to_account_info();
+        let funded = badge_mint.lamports();
+        if funded == 0 {
+            let cpi_accounts = CreateAccount {
+                from: ctx.accounts.contributor.to_account_info(),
+                to: badge_mint.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(system.clone(), cpi_accounts, badge_signer);
+            system_program::create_account(cpi_ctx, lamports, space as u64, &token_program.key())?;
+        } else {
+            // Someone pre-funded the address, which makes `create_account` fail
+            if funded < lamports {
+                let cpi_accounts = system_program::Transfer {
+                    from: ctx.accounts.contributor.to_account_info(),
+                    to: badge_mint.clone(),
+                };
+                system_program::transfer(CpiContext::new(system.clone(), cpi_accounts), lamports - funded)?;
+            }
+            let cpi_accounts = Allocate {
+                account_to_allocate: badge_mint.clone(),
+            };
+            system_program::allocate(
+                CpiContext::new_with_signer(system.clone(), cpi_accounts, badge_signer),
+                space as u64,
+            )?;
+            let cpi_accounts = Assign {
+                account_to_assign: badge_mint.clone(),
+            };
+            system_program::assign(
+                CpiContext::new_with_signer(system, cpi_accounts, badge_signer),
+                &token_program.key(),
+            )?;
+        }
+
+        // The extension has to be initialized before the mint itself
+        let cpi_accounts = NonTransferableMintInitialize {
+            token_program_id: token_program.clone(),
+            mint: badge_mint.clone(),
+        };
+        non_transferable_mint_initialize(CpiContext::new(token_program.clone(), cpi_accounts))?;
+        let cpi_accounts = InitializeMint2 { mint: badge_mint.clone() };
+        token_2022::initialize_mint2(
+            CpiContext::new(token_program.clone(), cpi_accounts),
+            0,
+            &_guard.campaign.key(),
+            None,
+        )?;
+
+        let cpi_accounts = associated_token::Create {
+            payer: ctx.accounts.contributor.to_account_info(),
+            associated_token: ctx.accounts.badge_account.to_account_info(),
+            authority: ctx.accounts.contributor.to_account_info(),
+            mint: badge_mint.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: token_program.clone(),
+        };
+        associated_token::create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            cpi_accounts,
+        ))?;
+
+        let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]];
+        let signer_seeds = &[seeds];
+        let cpi_accounts = token_2022::MintTo {
+            mint: badge_mint,
+            to: ctx.accounts.badge_account.to_account_info(),
+            authority: _guard.campaign.to_account_info(),
+        };
+        token_2022::mint_to(CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds), 1)?;
+
+        let contribution = &mut ctx.accounts.contribution;
+        contribution.badge_minted = true;
+        contribution.badge_tier = contribution.tier;
+        contribution.badge_rank = rank as u8;
+
+        Ok(())
+    }
+
+    // Emergency function to clear stuck reentrancy guards
+    pub fn clear_reentrancy_guard(ctx: Context<ClearReentrancyGuard>) -> Result<()> {
+        let campaign = &mut ctx.accounts.campaign;
+        let now = Clock::get()?.unix_timestamp;
+        
+        // Only allow clearing if guard has been stuck for too long
+        require!(
+            campaign.reentrancy_guard && 
+            (now - campaign.last_operation_timestamp) > REENTRANCY_GUARD_DURATION,
+            EscrowError::CannotClearGuard
+        );
+        
+        // Only campaign creator or metadata authority can clear stuck guards
+        let signer = ctx.accounts.authority.key();
+        require!(campaign.is_metadata_authority(&signer), EscrowError::Unauthorized);
+        
         campaign.reentrancy_guard = false;
         campaign.last_operation_timestamp = 0;
         
@@ -355,42 +1477,300 @@ pub mod cto_dex_escrow {
 }
 
 #[derive(Accounts)]
-pub struct InitCampaign<'info> {
+pub struct InitCampaign<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub pay_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        space = Campaign::SPACE,
+        seeds = [b"campaign", creator.key().as_ref()],
+        bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = creator,
+        space = Leaderboard::SPACE,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct EnableReceipts<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        constraint = campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+    pub pay_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = pay_mint.decimals,
+        mint::authority = campaign,
+        seeds = [b"receipt_mint", campaign.key().as_ref()],
+        bump
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        init,
+        payer = referrer,
+        space = Referral::SPACE,
+        seeds = [b"referral", campaign.key().as_ref(), referrer.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    amount: u64,
+    allowlist: Option<AllowlistProof>,
+    referrer: Option<Pubkey>,
+    memo: Option<String>,
+    beneficiary: Option<Pubkey>
+)]
+pub struct Contribute<'info> {
+    // Pays the tokens and rent; owns the contribution unless a beneficiary is named
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+    pub pay_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = contributor
+    )]
+    pub contributor_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = Contribution::SPACE,
+        seeds = [
+            b"contribution",
+            campaign.key().as_ref(),
+            beneficiary.unwrap_or(contributor.key()).as_ref()
+        ],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    // Receipt campaigns only: the receipt mint and any token account of it
+    #[account(mut)]
+    pub receipt_mint: Option<Account<'info, Mint>>,
+    #[account(mut)]
+    pub receipt_account: Option<Account<'info, TokenAccount>>,
+    // Required when the contribution is attributed to a referrer
+    #[account(mut)]
+    pub referral: Option<Account<'info, Referral>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitMetadata<'info> {
+    pub submitter: Signer<'info>,
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct VoteMetadata<'info> {
+    pub contributor: Signer<'info>,
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+}
+
+#[derive(Accounts)]
+pub struct Finalize<'info> {
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
     pub pay_mint: Account<'info, Mint>,
+    // Token account of the refund recipient (the contributor unless one is registered)
     #[account(
-        init,
-        payer = creator,
-        space = Campaign::SPACE,
-        seeds = [b"campaign", creator.key().as_ref()],
+        mut,
+        token::mint = pay_mint,
+        constraint = contributor_ata.owner == contribution.refund_owner() @ EscrowError::InvalidRefundAccount
+    )]
+    pub contributor_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
         bump
     )]
+    pub contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RefundFor<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+    /// CHECK: only used to derive the contribution PDA
+    pub contributor: UncheckedAccount<'info>,
+    /// CHECK: only used as the refund ATA's authority; must be the contribution's refund owner
+    #[account(address = contribution.refund_owner() @ EscrowError::InvalidRefundAccount)]
+    pub recipient: UncheckedAccount<'info>,
+    #[account(mut)]
     pub campaign: Account<'info, Campaign>,
+    pub pay_mint: Account<'info, Mint>,
     #[account(
-        init,
-        payer = creator,
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = pay_mint,
+        associated_token::authority = recipient
+    )]
+    pub recipient_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
         associated_token::mint = pay_mint,
         associated_token::authority = campaign
     )]
     pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct Contribute<'info> {
+pub struct BatchRefund<'info> {
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+    pub pay_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemReceipts<'info> {
+    pub holder: Signer<'info>,
+    #[account(
+        mut,
+        constraint = campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+    pub pay_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, address = campaign.receipt_mint @ EscrowError::InvalidReceiptMint)]
+    pub receipt_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = receipt_mint,
+        token::authority = holder
+    )]
+    pub holder_receipt_account: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = pay_mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawContribution<'info> {
     #[account(mut)]
     pub contributor: Signer<'info>,
     #[account(mut)]
     pub campaign: Account<'info, Campaign>,
     pub pay_mint: Account<'info, Mint>,
+    // Token account of the refund recipient (the contributor unless one is registered)
     #[account(
         mut,
-        associated_token::mint = pay_mint,
-        associated_token::authority = contributor
+        token::mint = pay_mint,
+        constraint = contributor_ata.owner == contribution.refund_owner() @ EscrowError::InvalidRefundAccount
     )]
     pub contributor_ata: Account<'info, TokenAccount>,
     #[account(
@@ -399,61 +1779,235 @@ pub struct Contribute<'info> {
         associated_token::authority = campaign
     )]
     pub vault: Account<'info, TokenAccount>,
+    // Receives the withdrawal penalty; only required when the campaign charges one
     #[account(
-        init_if_needed,
-        payer = contributor,
-        space = Contribution::SPACE,
+        mut,
+        token::mint = pay_mint,
+        constraint = creator_ata.owner == campaign.creator @ EscrowError::Unauthorized
+    )]
+    pub creator_ata: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
         seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
         bump
     )]
     pub contribution: Account<'info, Contribution>,
-    pub system_program: Program<'info, System>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    // Required when the contribution is attributed to a referrer
+    #[account(mut)]
+    pub referral: Option<Account<'info, Referral>>,
     pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct SubmitMetadata<'info> {
-    pub submitter: Signer<'info>,
+pub struct ExtendDeadline<'info> {
+    pub creator: Signer<'info>,
     #[account(mut)]
     pub campaign: Account<'info, Campaign>,
 }
 
 #[derive(Accounts)]
-pub struct Finalize<'info> {
+pub struct VoteDeadlineExtension<'info> {
+    pub contributor: Signer<'info>,
     #[account(mut)]
     pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
 }
 
 #[derive(Accounts)]
-pub struct Refund<'info> {
+pub struct MigrateContribution<'info> {
     #[account(mut)]
     pub contributor: Signer<'info>,
+    pub pay_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        constraint = source_campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub source_campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = source_campaign
+    )]
+    pub source_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"contribution", source_campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub source_contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", source_campaign.key().as_ref()],
+        bump = source_leaderboard.bump
+    )]
+    pub source_leaderboard: Box<Account<'info, Leaderboard>>,
+    #[account(
+        mut,
+        constraint = campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = Contribution::SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeMerge<'info> {
+    pub creator: Signer<'info>,
     #[account(mut)]
     pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMerge<'info> {
+    pub creator: Signer<'info>,
     pub pay_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        constraint = source_campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub source_campaign: Account<'info, Campaign>,
     #[account(
         mut,
         associated_token::mint = pay_mint,
-        associated_token::authority = contributor
+        associated_token::authority = source_campaign
     )]
-    pub contributor_ata: Account<'info, TokenAccount>,
+    pub source_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
     #[account(
         mut,
         associated_token::mint = pay_mint,
         associated_token::authority = campaign
     )]
     pub vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimMergedContribution<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(
+        constraint = source_campaign.merge_target == campaign.key() @ EscrowError::InvalidMergeTarget
+    )]
+    pub source_campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        seeds = [b"contribution", source_campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub source_contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        constraint = campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+    pub pay_mint: Account<'info, Mint>,
     #[account(
         mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    // Only needed when the destination has failed: the source position's refund owner
+    #[account(mut, token::mint = pay_mint)]
+    pub refund_ata: Option<Account<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = Contribution::SPACE,
         seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
         bump
     )]
     pub contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct TransferContribution<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    /// CHECK: only used to derive the new owner's contribution PDA
+    pub new_owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        close = contributor,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = Contribution::SPACE,
+        seeds = [b"contribution", campaign.key().as_ref(), new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_contribution: Account<'info, Contribution>,
+    #[account(
+        mut,
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRefundRecipient<'info> {
+    pub contributor: Signer<'info>,
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+}
+
 #[derive(Accounts)]
 pub struct SetMerchantHash<'info> {
     pub authority: Signer<'info>,
@@ -479,11 +2033,80 @@ pub struct Payout<'info> {
         associated_token::authority = campaign
     )]
     pub vault: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub merchant_ata: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub merchant_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralReward<'info> {
+    pub referrer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = campaign.pay_mint == pay_mint.key() @ EscrowError::PayMintMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+    pub pay_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = pay_mint,
+        associated_token::authority = campaign
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"referral", campaign.key().as_ref(), referrer.key().as_ref()],
+        bump = referral.bump
+    )]
+    pub referral: Account<'info, Referral>,
+    #[account(
+        mut,
+        token::mint = pay_mint,
+        token::authority = referrer
+    )]
+    pub referrer_ata: Account<'info, TokenAccount>,
     pub token_program: Program<'info, Token>,
 }
 
+#[derive(Accounts)]
+pub struct MintBadge<'info> {
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(mut)]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        mut,
+        seeds = [b"contribution", campaign.key().as_ref(), contributor.key().as_ref()],
+        bump
+    )]
+    pub contribution: Account<'info, Contribution>,
+    #[account(
+        seeds = [b"leaderboard", campaign.key().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Box<Account<'info, Leaderboard>>,
+    /// CHECK: created in the handler as a Token-2022 NonTransferable mint
+    #[account(
+        mut,
+        seeds = [b"badge", contribution.key().as_ref()],
+        bump
+    )]
+    pub badge_mint: UncheckedAccount<'info>,
+    /// CHECK: the contributor's badge ATA, created in the handler
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &contributor.key(),
+            &badge_mint.key(),
+            &token_2022_program.key()
+        )
+    )]
+    pub badge_account: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
 #[derive(Accounts)]
 pub struct ClearReentrancyGuard<'info> {
     #[account(mut)]
@@ -491,22 +2114,126 @@ pub struct ClearReentrancyGuard<'info> {
     pub authority: Signer<'info>,
 }
 
+// Returns a failed campaign's contribution to `destination`, which the caller's
+// account constraints have tied to the contribution's owner
+fn process_refund<'info>(
+    campaign: &mut Account<'info, Campaign>,
+    contribution: &mut Contribution,
+    leaderboard: &mut Leaderboard,
+    vault: &mut Account<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+) -> Result<()> {
+    // Copy the fields we need before mutable borrow
+    let creator = campaign.creator;
+    let bump = campaign.bump;
+    require!(campaign.status == CampaignStatus::Failed as u8, EscrowError::WrongStatus);
+    require!(campaign.receipt_mint == Pubkey::default(), EscrowError::ReceiptCampaign);
+
+    require!(!contribution.refunded, EscrowError::AlreadyRefunded);
+    let amount = contribution.amount;
+    require!(amount > 0, EscrowError::NothingToRefund);
+
+    // Validate vault has sufficient balance for refund
+    require!(vault.amount >= amount, EscrowError::InsufficientVaultBalance);
+
+    let mut _guard = ReentrancyScope::new(campaign)?;
+
+    // Seeds for vault authority = campaign PDA itself
+    let seeds: &[&[u8]] = &[b"campaign", creator.as_ref(), &[bump]]; // campaign PDA seeds
+    let signer_seeds = &[seeds];
+    // Transfer back to contributor
+    let cpi_accounts = Transfer {
+        from: vault.to_account_info(),
+        to: destination,
+        authority: _guard.campaign.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token::transfer(cpi_ctx, amount)?;
+    vault.reload()?;
+
+    contribution.refunded = true;
+
+    leaderboard.remove(&contribution.contributor);
+    _guard.campaign.sync_top_contributor(leaderboard);
+
+    Ok(())
+}
+
+// Receipt campaigns mint 1:1 receipts for every deposit into `receipt_account`
+fn mint_receipts<'info>(
+    campaign: &Account<'info, Campaign>,
+    receipt_mint: Option<&Account<'info, Mint>>,
+    receipt_account: Option<&Account<'info, TokenAccount>>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if campaign.receipt_mint == Pubkey::default() {
+        return Ok(());
+    }
+    let receipt_mint = receipt_mint.ok_or(EscrowError::ReceiptAccountsRequired)?;
+    let receipt_account = receipt_account.ok_or(EscrowError::ReceiptAccountsRequired)?;
+    require!(receipt_mint.key() == campaign.receipt_mint, EscrowError::InvalidReceiptMint);
+    require!(receipt_account.mint == campaign.receipt_mint, EscrowError::InvalidReceiptMint);
+
+    let seeds: &[&[u8]] = &[b"campaign", campaign.creator.as_ref(), &[campaign.bump]];
+    let signer_seeds = &[seeds];
+    let cpi_accounts = MintTo {
+        mint: receipt_mint.to_account_info(),
+        to: receipt_account.to_account_info(),
+        authority: campaign.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
+    token::mint_to(cpi_ctx, amount)
+}
+
 #[account]
+#[derive(Default)]
 pub struct Campaign {
     pub creator: Pubkey,
     pub pay_mint: Pubkey,
     pub bump: u8,
     pub target_amount: u64,
     pub total_contributed: u64,
+    pub start_time: i64,
     pub deadline: i64,
     pub status: u8,
     pub top_contributor: Pubkey,
     pub top_contributor_amount: u64,
+    pub metadata_authority: Pubkey,
     pub metadata_uri: String,
     pub metadata_hash: [u8; 32],
+    pub metadata_hash_algorithm: u8,
+    pub metadata_version: u32,
+    pub metadata_submitted_at: i64,
+    pub metadata_approve_weight: u64,
+    pub metadata_reject_weight: u64,
     pub merchant_hash: [u8; 32],
     pub merchant_hash_set: bool,
     pub delegate_authority: Pubkey,
+    pub contributor_count: u32,
+    pub min_contributors: u32,
+    pub max_per_contributor: u64,
+    pub allowlist_root: [u8; 32],
+    pub withdrawal_penalty_bps: u16,
+    pub withdrawal_cutoff: i64,
+    pub funding_mode: u8,
+    pub soft_cap: u64,
+    pub hard_cap: u64,
+    pub partial_fill: bool,
+    pub auto_finalize: bool,
+    pub extension_requires_consent: bool,
+    pub deadline_extended: bool,
+    pub extended_at: i64,
+    pub proposed_deadline: i64,
+    pub extension_approve_weight: u64,
+    pub extension_reject_weight: u64,
+    pub merge_target: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub tiers: Vec<Tier>,
+    pub referral_reward_bps: u16,
+    pub referred_total: u64,
+    pub referral_reserve: u64,
     pub reentrancy_guard: bool,
     pub last_operation_timestamp: i64,
 }
@@ -519,29 +2246,509 @@ impl Campaign {
         + 1  // bump
         + 8  // target_amount
         + 8  // total_contributed
+        + 8  // start_time
         + 8  // deadline
         + 1  // status
         + 32 // top_contributor
         + 8  // top_contributor_amount
+        + 32 // metadata_authority
         + 4 + Self::MAX_URI_LEN // metadata_uri
         + 32 // metadata_hash
+        + 1  // metadata_hash_algorithm
+        + 4  // metadata_version
+        + 8  // metadata_submitted_at
+        + 8  // metadata_approve_weight
+        + 8  // metadata_reject_weight
         + 32 // merchant_hash
         + 1  // merchant_hash_set
         + 32 // delegate_authority
+        + 4  // contributor_count
+        + 4  // min_contributors
+        + 8  // max_per_contributor
+        + 32 // allowlist_root
+        + 2  // withdrawal_penalty_bps
+        + 8  // withdrawal_cutoff
+        + 1  // funding_mode
+        + 8  // soft_cap
+        + 8  // hard_cap
+        + 1  // partial_fill
+        + 1  // auto_finalize
+        + 1  // extension_requires_consent
+        + 1  // deadline_extended
+        + 8  // extended_at
+        + 8  // proposed_deadline
+        + 8  // extension_approve_weight
+        + 8  // extension_reject_weight
+        + 32 // merge_target
+        + 32 // receipt_mint
+        + 4 + Tier::SPACE * MAX_TIERS // tiers
+        + 2  // referral_reward_bps
+        + 8  // referred_total
+        + 8  // referral_reserve
         + 1  // reentrancy_guard
         + 8; // last_operation_timestamp
+
+    // Checks shared by every path that adds funds to a pending campaign
+    pub fn validate_contribution(
+        &self,
+        contributor: &Pubkey,
+        prior_amount: u64,
+        amount: u64,
+        allowlist: Option<&AllowlistProof>,
+        now: i64,
+    ) -> Result<()> {
+        require!(self.status == CampaignStatus::Pending as u8, EscrowError::WrongStatus);
+        require!(now >= self.start_time, EscrowError::CampaignNotStarted);
+        require!(now <= self.deadline, EscrowError::DeadlinePassed);
+
+        // Validate campaign hasn't exceeded its hard cap
+        let new_total = self.total_contributed
+            .checked_add(amount)
+            .ok_or(EscrowError::Overflow)?;
+        require!(new_total <= self.hard_cap, EscrowError::ExceedsTarget);
+
+        let new_contribution_amount = prior_amount
+            .checked_add(amount)
+            .ok_or(EscrowError::Overflow)?;
+        self.validate_position(contributor, new_contribution_amount, allowlist)
+    }
+
+    // Per-wallet limits on a contributor's cumulative position
+    pub fn validate_position(
+        &self,
+        contributor: &Pubkey,
+        new_contribution_amount: u64,
+        allowlist: Option<&AllowlistProof>,
+    ) -> Result<()> {
+        // Enforce the per-wallet cap against the cumulative contribution
+        require!(
+            self.max_per_contributor == 0 || new_contribution_amount <= self.max_per_contributor,
+            EscrowError::ContributorCapExceeded
+        );
+
+        // Snapshot-gated campaigns only accept wallets proven against the allowlist root
+        if self.allowlist_root != [0u8; 32] {
+            let allowlist = allowlist.ok_or(EscrowError::NotAllowlisted)?;
+            require!(allowlist.proof.len() <= MAX_ALLOWLIST_PROOF_LEN, EscrowError::AllowlistProofTooLong);
+            let leaf = allowlist_leaf(contributor, allowlist.cap);
+            require!(
+                verify_allowlist_proof(&self.allowlist_root, leaf, &allowlist.proof),
+                EscrowError::NotAllowlisted
+            );
+            require!(
+                allowlist.cap == 0 || new_contribution_amount <= allowlist.cap,
+                EscrowError::AllowlistCapExceeded
+            );
+        }
+
+        Ok(())
+    }
+
+    // Books validated funds that have already reached the vault
+    pub fn record_contribution(
+        &mut self,
+        campaign_key: Pubkey,
+        contribution: &mut Contribution,
+        leaderboard: &mut Leaderboard,
+        contributor: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        self.total_contributed = self
+            .total_contributed
+            .checked_add(amount)
+            .ok_or(EscrowError::Overflow)?;
+        if self.receipt_mint == Pubkey::default() {
+            self.credit_contribution(campaign_key, contribution, leaderboard, contributor, amount)?;
+        } else {
+            // Receipt holdings, not deposits, decide who backs the campaign
+            contribution.contributor = contributor;
+            contribution.campaign = campaign_key;
+            contribution.amount = contribution
+                .amount
+                .checked_add(amount)
+                .ok_or(EscrowError::Overflow)?;
+        }
+
+        // Opt-in: the contribution that fills the campaign finalizes it, exactly as `finalize` would
+        if self.auto_finalize && self.is_full() {
+            self.mark_succeeded();
+        }
+
+        Ok(())
+    }
+
+    // Assigns `amount` already counted in `total_contributed` to a contributor's position
+    pub fn credit_contribution(
+        &mut self,
+        campaign_key: Pubkey,
+        contribution: &mut Contribution,
+        leaderboard: &mut Leaderboard,
+        contributor: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        if contribution.amount == 0 {
+            contribution.contributor = contributor;
+            contribution.campaign = campaign_key;
+            contribution.refunded = false;
+            self.contributor_count = self
+                .contributor_count
+                .checked_add(1)
+                .ok_or(EscrowError::Overflow)?;
+        }
+        contribution.amount = contribution
+            .amount
+            .checked_add(amount)
+            .ok_or(EscrowError::Overflow)?;
+        self.assign_tier(contribution);
+
+        // Rank the new cumulative amount; the leaderboard head is the top contributor
+        leaderboard.record(contributor, contribution.amount)?;
+        self.sync_top_contributor(leaderboard);
+
+        Ok(())
+    }
+
+    // Moves a contribution to the highest tier it qualifies for that still has a
+    // free slot (or that it already holds), releasing its previous slot
+    pub fn assign_tier(&mut self, contribution: &mut Contribution) {
+        let current = contribution.tier as usize;
+        let best = self
+            .tiers
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(i, tier)| {
+                contribution.amount >= tier.min_amount
+                    && (i + 1 == current || tier.max_slots == 0 || tier.filled < tier.max_slots)
+            })
+            .map_or(0, |(i, _)| i + 1);
+        if best == current {
+            return;
+        }
+        if current > 0 {
+            self.tiers[current - 1].filled = self.tiers[current - 1].filled.saturating_sub(1);
+        }
+        if best > 0 {
+            self.tiers[best - 1].filled += 1;
+        }
+        contribution.tier = best as u8;
+    }
+
+    // A full campaign (hard cap reached, enough distinct backers) can succeed before the deadline
+    pub fn is_full(&self) -> bool {
+        self.total_contributed >= self.hard_cap && self.contributor_count >= self.min_contributors
+    }
+
+    // Success threshold once the deadline has passed; equals the target for all-or-nothing
+    pub fn soft_cap_met(&self) -> bool {
+        self.total_contributed >= self.soft_cap && self.contributor_count >= self.min_contributors
+    }
+
+    // Freezes the privileged contributor role; `top_contributor` keeps moving
+    // during `contribute`, so its value at finalize is what counts.
+    pub fn mark_succeeded(&mut self) {
+        self.status = CampaignStatus::Succeeded as u8;
+        self.metadata_authority = self.top_contributor;
+    }
+
+    // The creator always qualifies; the top contributor only once frozen at finalize
+    pub fn is_metadata_authority(&self, signer: &Pubkey) -> bool {
+        *signer == self.creator
+            || (self.metadata_authority != Pubkey::default() && *signer == self.metadata_authority)
+    }
+
+    pub fn sync_top_contributor(&mut self, leaderboard: &Leaderboard) {
+        match leaderboard.top() {
+            Some(entry) => {
+                self.top_contributor = entry.contributor;
+                self.top_contributor_amount = entry.amount;
+            }
+            None => {
+                self.top_contributor = Pubkey::default();
+                self.top_contributor_amount = 0;
+            }
+        }
+    }
+
+    // Caps a contributor's recorded vote weight at what they still have in the
+    // campaign, removing the difference from the tallies.
+    pub fn sync_votes(&mut self, contribution: &mut Contribution) -> Result<()> {
+        if contribution.metadata_vote_version == self.metadata_version
+            && contribution.metadata_vote_weight > contribution.amount
+        {
+            let released = contribution.metadata_vote_weight - contribution.amount;
+            let tally = if contribution.metadata_vote_approve {
+                &mut self.metadata_approve_weight
+            } else {
+                &mut self.metadata_reject_weight
+            };
+            *tally = tally.checked_sub(released).ok_or(EscrowError::Overflow)?;
+            contribution.metadata_vote_weight = contribution.amount;
+        }
+        if !self.deadline_extended
+            && contribution.extension_vote != ExtensionVote::None as u8
+            && contribution.extension_vote_weight > contribution.amount
+        {
+            let released = contribution.extension_vote_weight - contribution.amount;
+            let tally = if contribution.extension_vote == ExtensionVote::Approve as u8 {
+                &mut self.extension_approve_weight
+            } else {
+                &mut self.extension_reject_weight
+            };
+            *tally = tally.checked_sub(released).ok_or(EscrowError::Overflow)?;
+            contribution.extension_vote_weight = contribution.amount;
+        }
+        Ok(())
+    }
+
+    // Metadata is approved once a majority of all contributed funds approves it, or
+    // when the approval window closes without more rejecting than approving weight.
+    // Silence counts as consent so low turnout cannot lock a succeeded campaign's
+    // vault; rejected metadata has to be resubmitted for a new round.
+    pub fn metadata_approved(&self, now: i64) -> bool {
+        if self.metadata_version == 0 {
+            return false;
+        }
+        let approve = self.metadata_approve_weight as u128;
+        let reject = self.metadata_reject_weight as u128;
+        if approve * 2 > self.total_contributed as u128 {
+            return true;
+        }
+        let window_closed = now > self.metadata_submitted_at.saturating_add(METADATA_APPROVAL_WINDOW);
+        window_closed && approve >= reject
+    }
+}
+
+// Optional campaign parameters chosen at `init_campaign`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct CampaignOptions {
+    // Distinct backers `finalize` requires in addition to the target amount
+    pub min_contributors: Option<u32>,
+    // Limit on a single wallet's cumulative contribution
+    pub contribution_cap: Option<ContributionCap>,
+    // Merkle root of `allowlist_leaf` hashes; only listed wallets may contribute
+    pub allowlist_root: Option<[u8; 32]>,
+    // Share of a withdrawal kept as a penalty and paid to the creator
+    pub withdrawal_penalty_bps: Option<u16>,
+    // Seconds before the deadline after which withdrawals are closed
+    pub withdrawal_cutoff_secs: Option<i64>,
+    // All-or-nothing by default; flexible campaigns must also set `soft_cap`
+    pub funding_mode: Option<FundingMode>,
+    // Amount at which a flexible campaign succeeds once the deadline passes
+    pub soft_cap: Option<u64>,
+    // Maximum the campaign accepts (at least the target); contributions above
+    // the target act as a buffer and the campaign stays open until the deadline
+    pub hard_cap: Option<u64>,
+    // Accept the remaining capacity instead of rejecting contributions that overshoot
+    pub partial_fill: bool,
+    // Transition to `Succeeded` inside `contribute` once the campaign is full
+    pub auto_finalize: bool,
+    // When contributions open; duration limits are measured from here
+    pub start_unix: Option<i64>,
+    // Deadline extensions need approval from contributors holding a majority of funds
+    pub extension_requires_consent: bool,
+    // Up to `MAX_TIERS` perk tiers, by increasing minimum amount
+    pub tiers: Vec<TierConfig>,
+    // Share of referred contributions paid to referrers once the campaign is paid
+    pub referral_reward_bps: Option<u16>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TierConfig {
+    pub min_amount: u64,
+    pub label: String,
+    // Contributions the tier can hold at once (0 = unlimited)
+    pub max_slots: u32,
+}
+
+// A campaign's tier and how many of its slots are taken
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Tier {
+    pub min_amount: u64,
+    pub label: String,
+    pub max_slots: u32,
+    pub filled: u32,
+}
+
+impl Tier {
+    pub const SPACE: usize = 8 + 4 + MAX_TIER_LABEL_LEN + 4 + 4;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContributionCap {
+    Absolute(u64),
+    PercentOfTargetBps(u16),
+}
+
+// Membership proof for allowlisted campaigns. `cap` is the per-wallet limit
+// encoded in the leaf (0 = no limit beyond the campaign's own).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct AllowlistProof {
+    pub cap: u64,
+    pub proof: Vec<[u8; 32]>,
 }
 
 #[account]
+#[derive(Default)]
 pub struct Contribution {
     pub contributor: Pubkey,
     pub campaign: Pubkey,
     pub amount: u64,
     pub refunded: bool,
+    pub metadata_vote_version: u32,
+    pub metadata_vote_approve: bool,
+    pub metadata_vote_weight: u64,
+    pub extension_vote: u8,
+    pub extension_vote_weight: u64,
+    pub refund_recipient: Pubkey,
+    pub badge_minted: bool,
+    // `tier` and leaderboard standing (`BadgeRank`) when the badge was minted
+    pub badge_tier: u8,
+    pub badge_rank: u8,
+    // 1-based index into `Campaign.tiers`; 0 = no tier
+    pub tier: u8,
+    pub referrer: Pubkey,
+    pub memo: String,
+    pub last_deposit_at: i64,
 }
 
 impl Contribution {
-    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1;
+    pub const SPACE: usize = 8  // discriminator
+        + 32 // contributor
+        + 32 // campaign
+        + 8  // amount
+        + 1  // refunded
+        + 4  // metadata_vote_version
+        + 1  // metadata_vote_approve
+        + 8  // metadata_vote_weight
+        + 1  // extension_vote
+        + 8  // extension_vote_weight
+        + 32 // refund_recipient
+        + 1  // badge_minted
+        + 1  // badge_tier
+        + 1  // badge_rank
+        + 1  // tier
+        + 32 // referrer
+        + 4 + MAX_MEMO_LEN // memo
+        + 8; // last_deposit_at
+
+    // Wallet whose token account receives refunds
+    pub fn refund_owner(&self) -> Pubkey {
+        if self.refund_recipient == Pubkey::default() {
+            self.contributor
+        } else {
+            self.refund_recipient
+        }
+    }
+}
+
+// Contributions attributed to one referrer of a campaign
+#[account]
+pub struct Referral {
+    pub campaign: Pubkey,
+    pub referrer: Pubkey,
+    pub bump: u8,
+    pub referred_amount: u64,
+    pub referred_count: u32,
+    pub reward_claimed: bool,
+}
+
+impl Referral {
+    pub const SPACE: usize = 8  // discriminator
+        + 32 // campaign
+        + 32 // referrer
+        + 1  // bump
+        + 8  // referred_amount
+        + 4  // referred_count
+        + 1; // reward_claimed
+}
+
+#[event]
+pub struct ContributionMemo {
+    pub campaign: Pubkey,
+    pub contributor: Pubkey,
+    // Signer who sent the memo; differs from `contributor` when contributing for a beneficiary
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub memo: String,
+}
+
+// Top contributors of a campaign, ordered by amount (desc) and then by who
+// reached their amount first. Entries that drop off are not backfilled from
+// outside the board; they re-enter on their next contribution.
+#[account]
+pub struct Leaderboard {
+    pub campaign: Pubkey,
+    pub bump: u8,
+    pub len: u8,
+    pub next_seq: u64,
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub seq: u64,
+}
+
+impl LeaderboardEntry {
+    pub const SPACE: usize = 32 + 8 + 8;
+}
+
+impl Leaderboard {
+    pub const SPACE: usize = 8  // discriminator
+        + 32 // campaign
+        + 1  // bump
+        + 1  // len
+        + 8  // next_seq
+        + LeaderboardEntry::SPACE * LEADERBOARD_SIZE; // entries
+
+    pub fn entries(&self) -> &[LeaderboardEntry] {
+        &self.entries[..self.len as usize]
+    }
+
+    pub fn top(&self) -> Option<&LeaderboardEntry> {
+        self.entries().first()
+    }
+
+    // Upserts a contributor's cumulative amount. Work is bounded by LEADERBOARD_SIZE.
+    pub fn record(&mut self, contributor: Pubkey, amount: u64) -> Result<()> {
+        if amount == 0 {
+            self.remove(&contributor);
+            return Ok(());
+        }
+
+        let seq = self.next_seq;
+        self.next_seq = seq.checked_add(1).ok_or(EscrowError::Overflow)?;
+        let entry = LeaderboardEntry { contributor, amount, seq };
+
+        let len = self.len as usize;
+        if let Some(i) = self.entries().iter().position(|e| e.contributor == contributor) {
+            self.entries[i] = entry;
+        } else if len < LEADERBOARD_SIZE {
+            self.entries[len] = entry;
+            self.len += 1;
+        } else if amount > self.entries[len - 1].amount {
+            // Ties keep the incumbent, who reached the amount first
+            self.entries[len - 1] = entry;
+        } else {
+            return Ok(());
+        }
+
+        let len = self.len as usize;
+        self.entries[..len].sort_unstable_by(|a, b| b.amount.cmp(&a.amount).then(a.seq.cmp(&b.seq)));
+        Ok(())
+    }
+
+    pub fn remove(&mut self, contributor: &Pubkey) {
+        let len = self.len as usize;
+        if let Some(i) = self.entries().iter().position(|e| e.contributor == *contributor) {
+            self.entries.copy_within(i + 1..len, i);
+            self.entries[len - 1] = LeaderboardEntry::default();
+            self.len -= 1;
+        }
+    }
 }
 
 #[repr(u8)]
@@ -550,6 +2757,131 @@ pub enum CampaignStatus {
     Succeeded = 1,
     Failed = 2,
     Paid = 3,
+    Merged = 4,
+}
+
+#[repr(u8)]
+pub enum ExtensionVote {
+    None = 0,
+    Approve = 1,
+    OptOut = 2,
+}
+
+// Leaderboard standing recorded with a supporter badge
+#[repr(u8)]
+pub enum BadgeRank {
+    Backer = 0,
+    Leaderboard = 1,
+    TopContributor = 2,
+}
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FundingMode {
+    #[default]
+    AllOrNothing = 0,
+    Flexible = 1,
+}
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataHashAlgorithm {
+    Sha256 = 0,
+    Keccak256 = 1,
+    Blake3 = 2,
+    IpfsMultihash = 3,
+}
+
+// Decodes the tag stored in `Campaign::metadata_hash_algorithm`
+impl TryFrom<u8> for MetadataHashAlgorithm {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::Sha256),
+            1 => Ok(Self::Keccak256),
+            2 => Ok(Self::Blake3),
+            3 => Ok(Self::IpfsMultihash),
+            _ => err!(EscrowError::InvalidHashAlgorithm),
+        }
+    }
+}
+
+/// Hashes `content` with the given algorithm, producing the 32-byte value that
+/// `submit_metadata` expects as `metadata_hash`.
+///
+/// For `IpfsMultihash` this is the digest part of a sha2-256 multihash over a
+/// raw-codec (CIDv1 `raw`) block, which equals the sha256 of the content.
+pub fn compute_metadata_hash(algorithm: MetadataHashAlgorithm, content: &[u8]) -> [u8; 32] {
+    match algorithm {
+        MetadataHashAlgorithm::Sha256 | MetadataHashAlgorithm::IpfsMultihash => {
+            Sha256::digest(content).into()
+        }
+        MetadataHashAlgorithm::Keccak256 => {
+            let mut hasher = Keccak::v256();
+            hasher.update(content);
+            let mut output = [0u8; 32];
+            hasher.finalize(&mut output);
+            output
+        }
+        MetadataHashAlgorithm::Blake3 => anchor_lang::solana_program::blake3::hash(content).to_bytes(),
+    }
+}
+
+/// Checks fetched metadata JSON against the on-chain commitment of a campaign.
+pub fn verify_metadata_hash(
+    algorithm: MetadataHashAlgorithm,
+    content: &[u8],
+    expected: &[u8; 32],
+) -> bool {
+    compute_metadata_hash(algorithm, content) == *expected
+}
+
+/// Extracts the 32-byte digest from a sha2-256 multihash (`0x12 0x20 || digest`),
+/// e.g. the multihash embedded in an IPFS CID.
+pub fn metadata_hash_from_multihash(multihash: &[u8]) -> Option<[u8; 32]> {
+    match multihash {
+        [0x12, 0x20, digest @ ..] => digest.try_into().ok(),
+        _ => None,
+    }
+}
+
+// Memos are short UTF-8 messages (`String` guarantees the encoding) without control characters
+pub fn validate_memo(memo: &str) -> Result<()> {
+    require!(
+        !memo.is_empty() && memo.len() <= MAX_MEMO_LEN,
+        EscrowError::InvalidMemo
+    );
+    require!(!memo.chars().any(char::is_control), EscrowError::InvalidMemo);
+    Ok(())
+}
+
+// Allowlist leaves and nodes are domain-separated keccak256 hashes; nodes hash
+// the sorted pair so proofs need no left/right flags.
+pub fn allowlist_leaf(wallet: &Pubkey, cap: u64) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(&[0x00]);
+    hasher.update(wallet.as_ref());
+    hasher.update(&cap.to_le_bytes());
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+pub fn allowlist_node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Keccak::v256();
+    hasher.update(&[0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    let mut output = [0u8; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+pub fn verify_allowlist_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| allowlist_node(&node, sibling));
+    computed == *root
 }
 
 #[error_code]
@@ -590,7 +2922,7 @@ pub enum EscrowError {
     InsufficientRent,
     #[msg("Insufficient balance")] 
     InsufficientBalance,
-    #[msg("Exceeds target")] 
+    #[msg("Exceeds hard cap")] 
     ExceedsTarget,
     #[msg("Empty URI")] 
     EmptyUri,
@@ -608,4 +2940,365 @@ pub enum EscrowError {
     ReentrancyDetected,
     #[msg("Cannot clear guard")] 
     CannotClearGuard,
-}
\ No newline at end of file
+    #[msg("Invalid hash algorithm")] 
+    InvalidHashAlgorithm,
+    #[msg("Metadata not submitted")] 
+    MetadataNotSubmitted,
+    #[msg("Metadata not approved")] 
+    MetadataNotApproved,
+    #[msg("Voting closed")] 
+    VotingClosed,
+    #[msg("Already voted")] 
+    AlreadyVoted,
+    #[msg("Nothing to vote with")] 
+    NothingToVote,
+    #[msg("Merchant hash already set")] 
+    MerchantHashAlreadySet,
+    #[msg("Invalid contribution cap")] 
+    InvalidContributionCap,
+    #[msg("Contributor cap exceeded")] 
+    ContributorCapExceeded,
+    #[msg("Not allowlisted")] 
+    NotAllowlisted,
+    #[msg("Allowlist proof too long")] 
+    AllowlistProofTooLong,
+    #[msg("Allowlist cap exceeded")] 
+    AllowlistCapExceeded,
+    #[msg("Invalid withdrawal terms")] 
+    InvalidWithdrawalTerms,
+    #[msg("Withdrawals are closed")] 
+    WithdrawalCutoff,
+    #[msg("Missing creator account")] 
+    MissingCreatorAccount,
+    #[msg("Invalid soft cap")] 
+    InvalidSoftCap,
+    #[msg("Invalid hard cap")] 
+    InvalidHardCap,
+    #[msg("Invalid start time")] 
+    InvalidStartTime,
+    #[msg("Campaign not started")] 
+    CampaignNotStarted,
+    #[msg("Deadline already extended")] 
+    DeadlineAlreadyExtended,
+    #[msg("No extension proposed")] 
+    NoExtensionProposed,
+    #[msg("Pay mint mismatch")] 
+    PayMintMismatch,
+    #[msg("Invalid merge target")] 
+    InvalidMergeTarget,
+    #[msg("Invalid batch")] 
+    InvalidBatch,
+    #[msg("Invalid contribution account")] 
+    InvalidContributionAccount,
+    #[msg("Invalid refund account")] 
+    InvalidRefundAccount,
+    #[msg("Invalid transfer target")] 
+    InvalidTransferTarget,
+    #[msg("Receipts must be enabled before the first contribution")] 
+    ReceiptsLocked,
+    #[msg("Receipt mint and account required")] 
+    ReceiptAccountsRequired,
+    #[msg("Invalid receipt mint")] 
+    InvalidReceiptMint,
+    #[msg("Not supported for receipt campaigns")] 
+    ReceiptCampaign,
+    #[msg("Badge already minted")] 
+    BadgeAlreadyMinted,
+    #[msg("Invalid tiers")] 
+    InvalidTiers,
+    #[msg("Invalid referral reward")] 
+    InvalidReferralReward,
+    #[msg("Invalid referrer")] 
+    InvalidReferrer,
+    #[msg("Referral account required")] 
+    ReferralAccountRequired,
+    #[msg("Payout would not leave enough for referral rewards")] 
+    ReferralRewardsUnfunded,
+    #[msg("Nothing to claim")] 
+    NothingToClaim,
+    #[msg("Reward already claimed")] 
+    RewardAlreadyClaimed,
+    #[msg("Invalid memo")] 
+    InvalidMemo,
+    #[msg("Contribution is not eligible for a badge")] 
+    NotEligibleForBadge,
+    #[msg("Contribution has a refund recipient; refund it instead")] 
+    RefundRecipientSet,
+    #[msg("Refund recipients of the merged positions differ")] 
+    RefundRecipientMismatch,
+    #[msg("Minimum contributor count cannot be reached")] 
+    InvalidMinContributors,
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex32(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn metadata_hashes_match_known_answers() {
+        let cases = [
+            (
+                MetadataHashAlgorithm::Sha256,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                MetadataHashAlgorithm::Keccak256,
+                "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+            ),
+            (
+                MetadataHashAlgorithm::Blake3,
+                "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85",
+            ),
+            (
+                MetadataHashAlgorithm::IpfsMultihash,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+        ];
+        for (algorithm, expected) in cases {
+            let expected = hex32(expected);
+            assert_eq!(compute_metadata_hash(algorithm, b"abc"), expected, "{algorithm:?}");
+            assert!(verify_metadata_hash(algorithm, b"abc", &expected));
+            assert!(!verify_metadata_hash(algorithm, b"abd", &expected));
+        }
+        assert_eq!(
+            compute_metadata_hash(MetadataHashAlgorithm::Keccak256, b""),
+            hex32("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+    }
+
+    #[test]
+    fn multihash_digest_requires_a_sha2_256_prefix() {
+        let digest = hex32("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let mut multihash = vec![0x12, 0x20];
+        multihash.extend_from_slice(&digest);
+        assert_eq!(metadata_hash_from_multihash(&multihash), Some(digest));
+
+        // sha3-256, a truncated digest and a trailing byte are all rejected
+        let mut sha3 = multihash.clone();
+        sha3[0] = 0x16;
+        assert_eq!(metadata_hash_from_multihash(&sha3), None);
+        assert_eq!(metadata_hash_from_multihash(&multihash[..33]), None);
+        multihash.push(0);
+        assert_eq!(metadata_hash_from_multihash(&multihash), None);
+    }
+
+    #[test]
+    fn hash_algorithm_tags_round_trip() {
+        for algorithm in [
+            MetadataHashAlgorithm::Sha256,
+            MetadataHashAlgorithm::Keccak256,
+            MetadataHashAlgorithm::Blake3,
+            MetadataHashAlgorithm::IpfsMultihash,
+        ] {
+            assert_eq!(MetadataHashAlgorithm::try_from(algorithm as u8).unwrap(), algorithm);
+        }
+        assert!(MetadataHashAlgorithm::try_from(4).is_err());
+    }
+
+    fn leaderboard() -> Leaderboard {
+        Leaderboard {
+            campaign: Pubkey::new_unique(),
+            bump: 0,
+            len: 0,
+            next_seq: 0,
+            entries: [LeaderboardEntry::default(); LEADERBOARD_SIZE],
+        }
+    }
+
+    fn amounts(board: &Leaderboard) -> Vec<u64> {
+        board.entries().iter().map(|e| e.amount).collect()
+    }
+
+    #[test]
+    fn leaderboard_orders_by_amount_then_arrival() {
+        let mut board = leaderboard();
+        let (a, b, c) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        board.record(a, 100).unwrap();
+        board.record(b, 300).unwrap();
+        board.record(c, 100).unwrap();
+        assert_eq!(amounts(&board), vec![300, 100, 100]);
+        assert_eq!(board.entries()[1].contributor, a);
+        assert_eq!(board.entries()[2].contributor, c);
+
+        // Reaching an existing amount later ranks behind whoever got there first
+        board.record(a, 300).unwrap();
+        let order: Vec<Pubkey> = board.entries().iter().map(|e| e.contributor).collect();
+        assert_eq!(order, vec![b, a, c]);
+        assert_eq!(board.top().unwrap().contributor, b);
+        assert_eq!(board.len, 3);
+    }
+
+    #[test]
+    fn leaderboard_evicts_the_last_entry_when_full() {
+        let mut board = leaderboard();
+        let wallets: Vec<Pubkey> = (0..LEADERBOARD_SIZE).map(|_| Pubkey::new_unique()).collect();
+        for (i, wallet) in wallets.iter().enumerate() {
+            board.record(*wallet, 100 + i as u64 * 10).unwrap();
+        }
+        assert_eq!(board.len as usize, LEADERBOARD_SIZE);
+        let last = *board.entries().last().unwrap();
+        assert_eq!(last.amount, 100);
+
+        // Equal to the last entry keeps the incumbent, lower is ignored
+        let newcomer = Pubkey::new_unique();
+        board.record(newcomer, 100).unwrap();
+        board.record(newcomer, 50).unwrap();
+        assert!(board.entries().iter().all(|e| e.contributor != newcomer));
+        assert_eq!(*board.entries().last().unwrap(), last);
+
+        board.record(newcomer, 105).unwrap();
+        assert_eq!(board.len as usize, LEADERBOARD_SIZE);
+        assert!(board.entries().iter().all(|e| e.contributor != last.contributor));
+        assert_eq!(board.entries().last().unwrap().contributor, newcomer);
+    }
+
+    #[test]
+    fn leaderboard_removal_compacts_entries() {
+        let mut board = leaderboard();
+        let wallets: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        for (i, wallet) in wallets.iter().enumerate() {
+            board.record(*wallet, 400 - i as u64 * 100).unwrap();
+        }
+
+        board.remove(&wallets[1]);
+        assert_eq!(amounts(&board), vec![400, 200, 100]);
+        assert_eq!(board.entries[3], LeaderboardEntry::default());
+
+        // Recording a zero amount removes the entry; unknown wallets are a no-op
+        board.record(wallets[0], 0).unwrap();
+        board.remove(&Pubkey::new_unique());
+        assert_eq!(amounts(&board), vec![200, 100]);
+        assert_eq!(board.top().unwrap().contributor, wallets[2]);
+
+        board.remove(&wallets[2]);
+        board.remove(&wallets[3]);
+        assert!(board.top().is_none());
+        assert_eq!(board.len, 0);
+    }
+
+    fn tiered_campaign() -> Campaign {
+        let tier = |min_amount, max_slots| Tier {
+            min_amount,
+            label: String::new(),
+            max_slots,
+            filled: 0,
+        };
+        Campaign {
+            tiers: vec![tier(100, 0), tier(500, 1)],
+            ..Campaign::default()
+        }
+    }
+
+    fn contribution(amount: u64) -> Contribution {
+        Contribution {
+            amount,
+            ..Contribution::default()
+        }
+    }
+
+    fn filled(campaign: &Campaign) -> Vec<u32> {
+        campaign.tiers.iter().map(|t| t.filled).collect()
+    }
+
+    #[test]
+    fn assigns_the_highest_qualifying_tier() {
+        let mut campaign = tiered_campaign();
+        let mut small = contribution(99);
+        campaign.assign_tier(&mut small);
+        assert_eq!(small.tier, 0);
+
+        let mut supporter = contribution(100);
+        campaign.assign_tier(&mut supporter);
+        assert_eq!(supporter.tier, 1);
+
+        // Growing within the top tier keeps its single slot
+        let mut patron = contribution(500);
+        campaign.assign_tier(&mut patron);
+        patron.amount = 900;
+        campaign.assign_tier(&mut patron);
+        assert_eq!(patron.tier, 2);
+        assert_eq!(filled(&campaign), vec![1, 1]);
+    }
+
+    #[test]
+    fn full_tiers_fall_back_to_the_next_one_down() {
+        let mut campaign = tiered_campaign();
+        let mut first = contribution(600);
+        let mut second = contribution(800);
+        campaign.assign_tier(&mut first);
+        campaign.assign_tier(&mut second);
+        assert_eq!((first.tier, second.tier), (2, 1));
+        assert_eq!(filled(&campaign), vec![1, 1]);
+    }
+
+    #[test]
+    fn withdrawals_and_transfers_release_slots() {
+        let mut campaign = tiered_campaign();
+        let mut first = contribution(600);
+        let mut second = contribution(800);
+        campaign.assign_tier(&mut first);
+        campaign.assign_tier(&mut second);
+
+        // A partial withdrawal below the minimum drops a tier and frees the slot
+        first.amount = 200;
+        campaign.assign_tier(&mut first);
+        assert_eq!(first.tier, 1);
+        assert_eq!(filled(&campaign), vec![2, 0]);
+        campaign.assign_tier(&mut second);
+        assert_eq!(second.tier, 2);
+        assert_eq!(filled(&campaign), vec![1, 1]);
+
+        // A transferred (emptied) position releases its tier entirely
+        second.amount = 0;
+        campaign.assign_tier(&mut second);
+        first.amount = 0;
+        campaign.assign_tier(&mut first);
+        assert_eq!((first.tier, second.tier), (0, 0));
+        assert_eq!(filled(&campaign), vec![0, 0]);
+    }
+
+    #[test]
+    fn receipt_deposits_rank_no_one() {
+        let mut campaign = Campaign {
+            receipt_mint: Pubkey::new_unique(),
+            hard_cap: 1_000,
+            auto_finalize: true,
+            ..Campaign::default()
+        };
+        let mut board = leaderboard();
+        let mut record = Contribution::default();
+        let depositor = Pubkey::new_unique();
+        campaign
+            .record_contribution(Pubkey::new_unique(), &mut record, &mut board, depositor, 1_000)
+            .unwrap();
+
+        assert_eq!((record.contributor, record.amount, record.tier), (depositor, 1_000, 0));
+        assert_eq!((campaign.total_contributed, campaign.contributor_count), (1_000, 0));
+        assert!(board.top().is_none());
+        assert_eq!(campaign.status, CampaignStatus::Succeeded as u8);
+        assert_eq!(campaign.metadata_authority, Pubkey::default());
+    }
+
+    #[test]
+    fn memos_are_bounded_and_printable() {
+        assert!(validate_memo("gm").is_ok());
+        assert!(validate_memo("für den Mond 🚀").is_ok());
+        assert!(validate_memo(&"a".repeat(MAX_MEMO_LEN)).is_ok());
+
+        // The bound is in bytes, so multi-byte characters count more than once
+        assert!(validate_memo(&"a".repeat(MAX_MEMO_LEN + 1)).is_err());
+        assert!(validate_memo(&"é".repeat(MAX_MEMO_LEN / 2 + 1)).is_err());
+        assert!(validate_memo("").is_err());
+
+        for memo in ["line\nbreak", "tab\there", "nul\0", "bell\u{7}", "del\u{7f}", "c1\u{85}"] {
+            assert!(validate_memo(memo).is_err(), "{memo:?}");
+        }
+    }
+}
@@ -0,0 +1,202 @@
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  Transaction,
+  TransactionInstruction,
+} from '@solana/web3.js';
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  MINT_SIZE,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccountInstruction,
+  createInitializeMint2Instruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token';
+import { BanksClient, Clock, ProgramTestContext, startAnchor } from 'solana-bankrun';
+import { createHash } from 'crypto';
+import fs from 'fs';
+import path from 'path';
+
+// Runs the built program (`anchor build`) in bankrun and measures what `batch_refund`
+// spends per (Contribution, ATA) pair, so MAX_BATCH_REFUND stays within the compute
+// unit limit an instruction gets without a ComputeBudget instruction.
+const ROOT = path.resolve(__dirname, '../../..');
+const PROGRAM_SO = path.join(ROOT, 'target/deploy/cto_dex_escrow.so');
+const describeBuilt = fs.existsSync(PROGRAM_SO) ? describe : describe.skip;
+
+describeBuilt('cto_dex_escrow batch_refund compute', () => {
+  // Mirrors MAX_BATCH_REFUND and MIN_CONTRIBUTION_AMOUNT in programs/cto_dex_escrow/src/lib.rs
+  const MAX_BATCH_REFUND = 8;
+  const MIN_CONTRIBUTION_AMOUNT = 1_000_000n;
+  const DEFAULT_COMPUTE_UNIT_LIMIT = 200_000n;
+  const PROGRAM_ID = new PublicKey('CfzHBxVGRyVC6TythNtmDkXVX1k9iJQvwzBasFDDbLsY');
+
+  let context: ProgramTestContext;
+  let client: BanksClient;
+
+  const discriminator = (name: string) =>
+    createHash('sha256').update(`global:${name}`).digest().subarray(0, 8);
+
+  const u64 = (value: bigint) => {
+    const out = Buffer.alloc(8);
+    out.writeBigUInt64LE(value);
+    return out;
+  };
+
+  const pda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, PROGRAM_ID)[0];
+
+  const send = async (signers: Keypair[], ...ixs: TransactionInstruction[]) => {
+    const tx = new Transaction();
+    tx.recentBlockhash = context.lastBlockhash;
+    tx.feePayer = signers[0].publicKey;
+    tx.add(...ixs);
+    tx.sign(...signers);
+    const result = await client.tryProcessTransaction(tx);
+    expect(result.result).toBeNull();
+    return result.meta!.computeUnitsConsumed;
+  };
+
+  const fund = (wallet: PublicKey) =>
+    context.setAccount(wallet, {
+      lamports: 10_000_000_000,
+      data: Buffer.alloc(0),
+      owner: SystemProgram.programId,
+      executable: false,
+    });
+
+  beforeAll(async () => {
+    context = await startAnchor(ROOT, [], []);
+    client = context.banksClient;
+  });
+
+  it('keeps a full batch within the default compute unit limit', async () => {
+    const creator = Keypair.generate();
+    fund(creator.publicKey);
+
+    const mint = Keypair.generate();
+    const rent = await client.getRent();
+    await send(
+      [creator, mint],
+      SystemProgram.createAccount({
+        fromPubkey: creator.publicKey,
+        newAccountPubkey: mint.publicKey,
+        lamports: Number(rent.minimumBalance(BigInt(MINT_SIZE))),
+        space: MINT_SIZE,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      createInitializeMint2Instruction(mint.publicKey, 6, creator.publicKey, null),
+    );
+
+    const campaign = pda(Buffer.from('campaign'), creator.publicKey.toBuffer());
+    const leaderboard = pda(Buffer.from('leaderboard'), campaign.toBuffer());
+    const vault = getAssociatedTokenAddressSync(mint.publicKey, campaign, true);
+
+    // Two hours out; every CampaignOption left at its default (17 zero bytes)
+    const now = (await client.getClock()).unixTimestamp;
+    const deadline = now + 7_200n;
+    await send(
+      [creator],
+      new TransactionInstruction({
+        programId: PROGRAM_ID,
+        keys: [
+          { pubkey: creator.publicKey, isSigner: true, isWritable: true },
+          { pubkey: mint.publicKey, isSigner: false, isWritable: false },
+          { pubkey: campaign, isSigner: false, isWritable: true },
+          { pubkey: vault, isSigner: false, isWritable: true },
+          { pubkey: leaderboard, isSigner: false, isWritable: true },
+          { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+          { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+          { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+        ],
+        data: Buffer.concat([
+          discriminator('init_campaign'),
+          u64(MIN_CONTRIBUTION_AMOUNT * 100n),
+          u64(deadline),
+          Buffer.alloc(17),
+        ]),
+      }),
+    );
+
+    // One position for a single-pair batch plus a full batch, all short of the target
+    const pairs: { contribution: PublicKey; ata: PublicKey }[] = [];
+    for (let i = 0; i < MAX_BATCH_REFUND + 1; i++) {
+      const contributor = Keypair.generate();
+      fund(contributor.publicKey);
+      const ata = getAssociatedTokenAddressSync(mint.publicKey, contributor.publicKey);
+      const contribution = pda(Buffer.from('contribution'), campaign.toBuffer(), contributor.publicKey.toBuffer());
+      await send(
+        [creator],
+        createAssociatedTokenAccountInstruction(creator.publicKey, ata, contributor.publicKey, mint.publicKey),
+        createMintToInstruction(mint.publicKey, ata, creator.publicKey, MIN_CONTRIBUTION_AMOUNT),
+      );
+      await send(
+        [contributor],
+        new TransactionInstruction({
+          programId: PROGRAM_ID,
+          keys: [
+            { pubkey: contributor.publicKey, isSigner: true, isWritable: true },
+            { pubkey: campaign, isSigner: false, isWritable: true },
+            { pubkey: mint.publicKey, isSigner: false, isWritable: false },
+            { pubkey: ata, isSigner: false, isWritable: true },
+            { pubkey: vault, isSigner: false, isWritable: true },
+            { pubkey: contribution, isSigner: false, isWritable: true },
+            { pubkey: leaderboard, isSigner: false, isWritable: true },
+            // receipt_mint, receipt_account, referral: not used
+            { pubkey: PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+            { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+            { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+          ],
+          // amount, then allowlist, referrer, memo and beneficiary all None
+          data: Buffer.concat([discriminator('contribute'), u64(MIN_CONTRIBUTION_AMOUNT), Buffer.alloc(4)]),
+        }),
+      );
+      pairs.push({ contribution, ata });
+    }
+
+    const clock = await client.getClock();
+    context.setClock(
+      new Clock(clock.slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, deadline + 1n),
+    );
+    await send(
+      [creator],
+      new TransactionInstruction({
+        programId: PROGRAM_ID,
+        keys: [{ pubkey: campaign, isSigner: false, isWritable: true }],
+        data: discriminator('finalize'),
+      }),
+    );
+
+    const batchRefund = (batch: typeof pairs) =>
+      new TransactionInstruction({
+        programId: PROGRAM_ID,
+        keys: [
+          { pubkey: campaign, isSigner: false, isWritable: true },
+          { pubkey: mint.publicKey, isSigner: false, isWritable: false },
+          { pubkey: vault, isSigner: false, isWritable: true },
+          { pubkey: leaderboard, isSigner: false, isWritable: true },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+          ...batch.flatMap(({ contribution, ata }) => [
+            { pubkey: contribution, isSigner: false, isWritable: true },
+            { pubkey: ata, isSigner: false, isWritable: true },
+          ]),
+        ],
+        data: discriminator('batch_refund'),
+      });
+
+    // No ComputeBudget instruction: each batch runs under the default limit
+    const single = await send([creator], batchRefund(pairs.slice(0, 1)));
+    const full = await send([creator], batchRefund(pairs.slice(1)));
+    const perPair = (full - single) / BigInt(MAX_BATCH_REFUND - 1);
+    console.log(`batch_refund: ${single} CU for 1 pair, ${full} CU for ${MAX_BATCH_REFUND}, ~${perPair} CU per pair`);
+
+    expect(full).toBeLessThanOrEqual(DEFAULT_COMPUTE_UNIT_LIMIT);
+  });
+});
@@ -1,4 +1,11 @@
-import { Connection, PublicKey } from '@solana/web3.js';
+import {
+  ComputeBudgetProgram,
+  Connection,
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+} from '@solana/web3.js';
 import { AnchorProvider, Program, Idl } from '@coral-xyz/anchor';
 import fs from 'fs';
 
@@ -37,4 +44,58 @@ describe('cto_dex_escrow basic', () => {
   });
 });
 
+describe('cto_dex_escrow batch_refund sizing', () => {
+  // Mirrors MAX_BATCH_REFUND in programs/cto_dex_escrow/src/lib.rs
+  const MAX_BATCH_REFUND = 8;
+  const PACKET_DATA_SIZE = 1232;
+  const PROGRAM_ID = new PublicKey('CfzHBxVGRyVC6TythNtmDkXVX1k9iJQvwzBasFDDbLsY');
 
+  const batchRefundTxSize = (pairs: number, computeBudget = false) => {
+    const cranker = Keypair.generate();
+    // campaign, pay_mint, vault, leaderboard, token_program
+    const fixed = Array.from({ length: 5 }, () => ({
+      pubkey: Keypair.generate().publicKey,
+      isSigner: false,
+      isWritable: true,
+    }));
+    // (Contribution, contributor ATA) pairs passed as remaining accounts
+    const remaining = Array.from({ length: pairs * 2 }, () => ({
+      pubkey: Keypair.generate().publicKey,
+      isSigner: false,
+      isWritable: true,
+    }));
+    const ix = new TransactionInstruction({
+      programId: PROGRAM_ID,
+      keys: [...fixed, ...remaining],
+      data: Buffer.alloc(8), // instruction discriminator, no args
+    });
+    const tx = new Transaction({
+      feePayer: cranker.publicKey,
+      recentBlockhash: Keypair.generate().publicKey.toBase58(),
+    });
+    if (computeBudget) {
+      tx.add(
+        ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 }),
+        ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 1 }),
+      );
+    }
+    tx.add(ix);
+    tx.sign(cranker);
+    return tx.serialize().length;
+  };
+
+  it('fits a full batch in a single legacy transaction', () => {
+    expect(batchRefundTxSize(MAX_BATCH_REFUND)).toBeLessThanOrEqual(PACKET_DATA_SIZE);
+  });
+
+  it('fits a full batch alongside compute budget instructions', () => {
+    // Compute use is covered by batch_refund_cu.test.ts; priority fees still need these
+    expect(batchRefundTxSize(MAX_BATCH_REFUND, true)).toBeLessThanOrEqual(PACKET_DATA_SIZE);
+  });
+
+  it('leaves headroom below the hard transaction size limit', () => {
+    // 13 pairs is the most a legacy transaction can carry; 14 no longer fits
+    expect(batchRefundTxSize(13)).toBeLessThanOrEqual(PACKET_DATA_SIZE);
+    expect(() => batchRefundTxSize(14)).toThrow();
+  });
+});